@@ -0,0 +1,609 @@
+//! Derive macro for [`display_full_error`](https://docs.rs/display_full_error).
+//!
+//! This crate is an implementation detail of the `derive` feature of
+//! `display_full_error`. It should not be used directly: enable the `derive`
+//! feature and use `display_full_error::DisplayFullError` instead.
+//!
+//! This crate has no dependencies: it only relies on the `proc_macro` API of
+//! the compiler. The supported input is intentionally restricted to
+//! non-generic structs and enums.
+#![deny(missing_docs)]
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Derive `Display` and `Error` for an error type, following the conventions
+/// expected by `display_full_error`.
+///
+/// The generated `Display` implementation only prints the local message of the
+/// error, configured with the `#[display(...)]` attribute. The generated
+/// `Error` implementation returns the field marked with `#[source]` (or named
+/// `source`) from `Error::source`.
+///
+/// The message must not include the source: formatters such as
+/// `DisplayFullError` print the source after the message, so including it in
+/// the message would print it twice. Referencing the source field in the
+/// message is a compilation error.
+///
+/// See the `derive` feature of `display_full_error` for usage examples.
+#[proc_macro_derive(DisplayFullError, attributes(display, source))]
+pub fn derive_display_full_error(input: TokenStream) -> TokenStream {
+  match expand(input) {
+    Ok(output) => output,
+    Err(e) => e.into_compile_error(),
+  }
+}
+
+/// Error reported to the user as a `compile_error!` invocation.
+struct Error {
+  message: String,
+  span: Span,
+}
+
+impl Error {
+  fn new(span: Span, message: impl Into<String>) -> Self {
+    Self {
+      message: message.into(),
+      span,
+    }
+  }
+
+  fn into_compile_error(self) -> TokenStream {
+    let mut message = Literal::string(&self.message);
+    message.set_span(self.span);
+    let mut args = Group::new(Delimiter::Brace, TokenStream::from(TokenTree::Literal(message)));
+    args.set_span(self.span);
+    let mut joint = Punct::new(':', Spacing::Joint);
+    joint.set_span(self.span);
+    let mut alone = Punct::new(':', Spacing::Alone);
+    alone.set_span(self.span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(self.span);
+    [
+      TokenTree::Punct(joint.clone()),
+      TokenTree::Punct(alone.clone()),
+      TokenTree::Ident(Ident::new("core", self.span)),
+      TokenTree::Punct(joint),
+      TokenTree::Punct(alone),
+      TokenTree::Ident(Ident::new("compile_error", self.span)),
+      TokenTree::Punct(bang),
+      TokenTree::Group(args),
+    ]
+    .into_iter()
+    .collect()
+  }
+}
+
+type Tokens = ::core::iter::Peekable<proc_macro::token_stream::IntoIter>;
+
+/// Parsed derive input
+struct Input {
+  name: Ident,
+  display: Option<Display>,
+  data: Data,
+}
+
+enum Data {
+  Struct(Fields),
+  Enum(Vec<Variant>),
+}
+
+struct Variant {
+  name: Ident,
+  display: Option<Display>,
+  fields: Fields,
+}
+
+enum Fields {
+  Named(Vec<Field>),
+  Unnamed(Vec<Field>),
+  Unit,
+}
+
+struct Field {
+  /// Field name, `None` for tuple fields
+  name: Option<Ident>,
+  /// Whether the field is marked with `#[source]`
+  source: bool,
+  /// Type of the field
+  ty: Vec<TokenTree>,
+}
+
+/// Arguments of the `#[display(...)]` attribute
+struct Display {
+  args: TokenStream,
+  span: Span,
+}
+
+/// Attributes relevant to this derive
+#[derive(Default)]
+struct Attrs {
+  display: Option<Display>,
+  source: bool,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+  let input = parse_input(input)?;
+  let mut output = String::new();
+  output.push_str(&expand_display(&input)?);
+  output.push_str(&expand_error(&input)?);
+  Ok(output.parse().expect("generated code is valid"))
+}
+
+fn parse_input(input: TokenStream) -> Result<Input, Error> {
+  let mut tokens: Tokens = input.into_iter().peekable();
+  let attrs = parse_attrs(&mut tokens)?;
+  if attrs.source {
+    return Err(Error::new(Span::call_site(), "`#[source]` is only supported on fields"));
+  }
+  skip_visibility(&mut tokens);
+  let kind = match tokens.next() {
+    Some(TokenTree::Ident(kind)) => kind,
+    _ => return Err(Error::new(Span::call_site(), "expected `struct` or `enum`")),
+  };
+  let name = match tokens.next() {
+    Some(TokenTree::Ident(name)) => name,
+    _ => return Err(Error::new(kind.span(), "expected type name")),
+  };
+  if let Some(TokenTree::Punct(p)) = tokens.peek() {
+    if p.as_char() == '<' {
+      return Err(Error::new(
+        p.span(),
+        "generic types are not supported by `#[derive(DisplayFullError)]`",
+      ));
+    }
+  }
+  let data = match kind.to_string().as_str() {
+    "struct" => Data::Struct(parse_fields(tokens.next())?),
+    "enum" => match tokens.next() {
+      Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => Data::Enum(parse_variants(body)?),
+      _ => return Err(Error::new(name.span(), "expected enum body")),
+    },
+    _ => {
+      return Err(Error::new(
+        kind.span(),
+        "`#[derive(DisplayFullError)]` only supports structs and enums",
+      ))
+    }
+  };
+  Ok(Input {
+    name,
+    display: attrs.display,
+    data,
+  })
+}
+
+fn parse_attrs(tokens: &mut Tokens) -> Result<Attrs, Error> {
+  let mut attrs = Attrs::default();
+  while let Some(TokenTree::Punct(p)) = tokens.peek() {
+    if p.as_char() != '#' {
+      break;
+    }
+    tokens.next();
+    let attr = match tokens.next() {
+      Some(TokenTree::Group(attr)) if attr.delimiter() == Delimiter::Bracket => attr,
+      _ => return Err(Error::new(Span::call_site(), "expected attribute")),
+    };
+    let mut attr_tokens = attr.stream().into_iter();
+    let name = match attr_tokens.next() {
+      Some(TokenTree::Ident(name)) => name,
+      _ => continue,
+    };
+    match name.to_string().as_str() {
+      "display" => {
+        let args = match attr_tokens.next() {
+          Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis => args,
+          _ => return Err(Error::new(name.span(), "expected `#[display(\"...\")]`")),
+        };
+        if attrs.display.is_some() {
+          return Err(Error::new(name.span(), "duplicate `#[display(...)]` attribute"));
+        }
+        attrs.display = Some(Display {
+          args: args.stream(),
+          span: name.span(),
+        });
+      }
+      "source" => attrs.source = true,
+      _ => {}
+    }
+  }
+  Ok(attrs)
+}
+
+fn skip_visibility(tokens: &mut Tokens) {
+  if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+    if ident.to_string() == "pub" {
+      tokens.next();
+      if let Some(TokenTree::Group(g)) = tokens.peek() {
+        if g.delimiter() == Delimiter::Parenthesis {
+          tokens.next();
+        }
+      }
+    }
+  }
+}
+
+fn parse_fields(body: Option<TokenTree>) -> Result<Fields, Error> {
+  match body {
+    None => Ok(Fields::Unit),
+    Some(TokenTree::Punct(p)) if p.as_char() == ';' || p.as_char() == ',' || p.as_char() == '=' => Ok(Fields::Unit),
+    Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+      let mut fields = Vec::new();
+      for field in split_top_level(g.stream()) {
+        let mut tokens: Tokens = field.into_iter().collect::<TokenStream>().into_iter().peekable();
+        let attrs = parse_attrs(&mut tokens)?;
+        skip_visibility(&mut tokens);
+        let name = match tokens.next() {
+          Some(TokenTree::Ident(name)) => name,
+          _ => return Err(Error::new(g.span(), "expected field name")),
+        };
+        match tokens.next() {
+          Some(TokenTree::Punct(p)) if p.as_char() == ':' => {}
+          _ => return Err(Error::new(name.span(), "expected `:`")),
+        }
+        fields.push(Field {
+          name: Some(name),
+          source: attrs.source,
+          ty: tokens.collect(),
+        });
+      }
+      Ok(Fields::Named(fields))
+    }
+    Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => {
+      let mut fields = Vec::new();
+      for field in split_top_level(g.stream()) {
+        let mut tokens: Tokens = field.into_iter().collect::<TokenStream>().into_iter().peekable();
+        let attrs = parse_attrs(&mut tokens)?;
+        skip_visibility(&mut tokens);
+        fields.push(Field {
+          name: None,
+          source: attrs.source,
+          ty: tokens.collect(),
+        });
+      }
+      Ok(Fields::Unnamed(fields))
+    }
+    Some(other) => Err(Error::new(other.span(), "unexpected token")),
+  }
+}
+
+fn parse_variants(body: Group) -> Result<Vec<Variant>, Error> {
+  let mut variants = Vec::new();
+  for variant in split_top_level(body.stream()) {
+    let mut tokens: Tokens = variant.into_iter().collect::<TokenStream>().into_iter().peekable();
+    let attrs = parse_attrs(&mut tokens)?;
+    if attrs.source {
+      return Err(Error::new(body.span(), "`#[source]` is only supported on fields"));
+    }
+    let name = match tokens.next() {
+      Some(TokenTree::Ident(name)) => name,
+      _ => return Err(Error::new(body.span(), "expected variant name")),
+    };
+    let fields = parse_fields(tokens.next())?;
+    variants.push(Variant {
+      name,
+      display: attrs.display,
+      fields,
+    });
+  }
+  Ok(variants)
+}
+
+/// Split a token stream on commas, ignoring commas nested in generic arguments.
+///
+/// Empty segments (e.g. due to a trailing comma) are skipped.
+fn split_top_level(stream: TokenStream) -> Vec<Vec<TokenTree>> {
+  let mut segments = Vec::new();
+  let mut current = Vec::new();
+  let mut depth: usize = 0;
+  let mut previous_arrow_start = false;
+  for token in stream {
+    let mut arrow_start = false;
+    if let TokenTree::Punct(p) = &token {
+      match p.as_char() {
+        ',' if depth == 0 => {
+          if !current.is_empty() {
+            segments.push(::core::mem::take(&mut current));
+          }
+          previous_arrow_start = false;
+          continue;
+        }
+        '<' => depth += 1,
+        '>' if !previous_arrow_start => depth = depth.saturating_sub(1),
+        '-' => arrow_start = p.spacing() == Spacing::Joint,
+        _ => {}
+      }
+    }
+    previous_arrow_start = arrow_start;
+    current.push(token);
+  }
+  if !current.is_empty() {
+    segments.push(current);
+  }
+  segments
+}
+
+impl Fields {
+  fn all(&self) -> &[Field] {
+    match self {
+      Fields::Named(fields) | Fields::Unnamed(fields) => fields,
+      Fields::Unit => &[],
+    }
+  }
+
+  /// Pattern binding all the fields by reference, to be used after the path of the struct or variant.
+  ///
+  /// Tuple fields are bound as `_0`, `_1`, etc.
+  fn binding_pattern(&self) -> String {
+    match self {
+      Fields::Named(fields) => {
+        let names: Vec<String> = fields.iter().enumerate().map(|(i, f)| f.binding(i)).collect();
+        format!(" {{ {} }}", names.join(", "))
+      }
+      Fields::Unnamed(fields) => {
+        let names: Vec<String> = (0..fields.len()).map(|i| format!("_{i}")).collect();
+        format!("({})", names.join(", "))
+      }
+      Fields::Unit => String::new(),
+    }
+  }
+
+  /// Find the source field, returning its index
+  fn source(&self) -> Result<Option<usize>, Error> {
+    let mut source = None;
+    for (i, field) in self.all().iter().enumerate() {
+      let is_source = field.source || field.name.as_ref().is_some_and(|name| name.to_string() == "source");
+      if is_source {
+        if source.is_some() {
+          let span = field.name.as_ref().map_or_else(Span::call_site, Ident::span);
+          return Err(Error::new(span, "multiple source fields"));
+        }
+        source = Some(i);
+      }
+    }
+    Ok(source)
+  }
+}
+
+impl Field {
+  /// Name of the field, as used in the `Display` bindings
+  fn binding(&self, index: usize) -> String {
+    match &self.name {
+      Some(name) => name.to_string(),
+      None => format!("_{index}"),
+    }
+  }
+
+  /// Whether the type of the field is syntactically an `Option`
+  fn is_option(&self) -> bool {
+    let mut last_ident = None;
+    for token in &self.ty {
+      match token {
+        TokenTree::Ident(ident) => last_ident = Some(ident.to_string()),
+        TokenTree::Punct(p) if p.as_char() == '<' => break,
+        _ => {}
+      }
+    }
+    last_ident.as_deref() == Some("Option")
+  }
+}
+
+/// Check that the message does not reference the source field
+///
+/// The source may be referenced inline in the format string (`{source}`), or
+/// passed as a positional or named argument (`"{}", source`).
+fn check_display(display: &Display, fields: &Fields) -> Result<(), Error> {
+  let source = match fields.source()? {
+    Some(source) => source,
+    None => return Ok(()),
+  };
+  let binding = fields.all()[source].binding(source);
+  let mut tokens = display.args.clone().into_iter();
+  let format = match tokens.next() {
+    Some(TokenTree::Literal(format)) => format.to_string(),
+    _ => return Ok(()),
+  };
+  let mut shadowed = false;
+  for arg in split_args(tokens) {
+    let (name, expr) = match arg.as_slice() {
+      [TokenTree::Ident(name), TokenTree::Punct(eq), expr @ ..] if eq.as_char() == '=' && is_expr_start(expr) => {
+        (Some(name.to_string()), expr)
+      }
+      expr => (None, expr),
+    };
+    if is_binding(expr, &binding) {
+      return Err(source_in_message(display.span, &binding));
+    }
+    shadowed |= name.as_deref() == Some(binding.as_str());
+  }
+  if !shadowed && placeholders(&format).any(|name| name == binding) {
+    return Err(source_in_message(display.span, &binding));
+  }
+  Ok(())
+}
+
+fn source_in_message(span: Span, binding: &str) -> Error {
+  Error::new(
+    span,
+    format!("the message must not include the source `{binding}`: it is already displayed as part of the error chain"),
+  )
+}
+
+/// Split the arguments following the format string on top-level commas
+fn split_args(tokens: impl Iterator<Item = TokenTree>) -> Vec<Vec<TokenTree>> {
+  let mut args = Vec::new();
+  let mut current = Vec::new();
+  for token in tokens {
+    match token {
+      TokenTree::Punct(p) if p.as_char() == ',' => {
+        if !current.is_empty() {
+          args.push(::core::mem::take(&mut current));
+        }
+      }
+      token => current.push(token),
+    }
+  }
+  if !current.is_empty() {
+    args.push(current);
+  }
+  args
+}
+
+/// Whether the tokens following `name =` start an expression, as opposed to a
+/// comparison such as `name == value`
+fn is_expr_start(expr: &[TokenTree]) -> bool {
+  !matches!(expr.first(), Some(TokenTree::Punct(p)) if p.as_char() == '=')
+}
+
+/// Whether the expression is the binding, possibly behind `&` or `*`
+fn is_binding(expr: &[TokenTree], binding: &str) -> bool {
+  let mut expr = expr;
+  while let [TokenTree::Punct(p), rest @ ..] = expr {
+    if p.as_char() != '&' && p.as_char() != '*' {
+      break;
+    }
+    expr = rest;
+  }
+  match expr {
+    [TokenTree::Ident(ident)] => ident.to_string() == binding,
+    [TokenTree::Group(group)] if group.delimiter() != Delimiter::Bracket => {
+      is_binding(&group.stream().into_iter().collect::<Vec<_>>(), binding)
+    }
+    _ => false,
+  }
+}
+
+/// Iterate over the names of the inline arguments of a format string literal
+///
+/// `{{` and `}}` are escaped braces. Positional placeholders such as `{}` or
+/// `{0}` yield their index or an empty name.
+fn placeholders(format: &str) -> impl Iterator<Item = &str> {
+  let mut rest = format;
+  ::core::iter::from_fn(move || loop {
+    let start = rest.find(['{', '}'])?;
+    let brace = rest[start..].chars().next()?;
+    let after = &rest[start + 1..];
+    if after.starts_with(brace) {
+      rest = &after[1..];
+      continue;
+    }
+    if brace == '}' {
+      rest = after;
+      continue;
+    }
+    let end = after.find('}').unwrap_or(after.len());
+    let spec = &after[..end];
+    rest = &after[end..];
+    let name = spec.split([':', '}']).next().unwrap_or_default().trim();
+    return Some(name);
+  })
+}
+
+fn missing_display(span: Span) -> Error {
+  Error::new(span, "missing `#[display(\"...\")]` attribute")
+}
+
+fn expand_display(input: &Input) -> Result<String, Error> {
+  let body = match &input.data {
+    Data::Struct(fields) => {
+      let display = input
+        .display
+        .as_ref()
+        .ok_or_else(|| missing_display(input.name.span()))?;
+      check_display(display, fields)?;
+      format!(
+        "#[allow(unused_variables)] let Self{} = self; ::core::write!(__formatter, {})",
+        fields.binding_pattern(),
+        display.args
+      )
+    }
+    Data::Enum(variants) => {
+      let mut arms = String::new();
+      for variant in variants {
+        let display = variant
+          .display
+          .as_ref()
+          .or(input.display.as_ref())
+          .ok_or_else(|| missing_display(variant.name.span()))?;
+        check_display(display, &variant.fields)?;
+        arms.push_str(&format!(
+          "#[allow(unused_variables)] Self::{}{} => ::core::write!(__formatter, {}),",
+          variant.name,
+          variant.fields.binding_pattern(),
+          display.args
+        ));
+      }
+      format!("match self {{ {arms} }}")
+    }
+  };
+  Ok(format!(
+    "#[automatically_derived] impl ::core::fmt::Display for {} {{ fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {{ {} }} }}",
+    input.name, body
+  ))
+}
+
+/// Expression returning the source, from a reference to the source field bound to `__source`
+fn source_expr(field: &Field) -> &'static str {
+  if field.is_option() {
+    "__source.as_ref().map(|source| source.as_dyn_error())"
+  } else {
+    "::core::option::Option::Some(__source.as_dyn_error())"
+  }
+}
+
+/// Struct pattern member for the field at the provided index
+fn source_member(field: &Field, index: usize) -> String {
+  match &field.name {
+    Some(name) => name.to_string(),
+    None => index.to_string(),
+  }
+}
+
+fn expand_error(input: &Input) -> Result<String, Error> {
+  let body = match &input.data {
+    Data::Struct(fields) => match fields.source()? {
+      Some(index) => {
+        let field = &fields.all()[index];
+        Some(format!(
+          "let Self {{ {}: __source, .. }} = self; {}",
+          source_member(field, index),
+          source_expr(field)
+        ))
+      }
+      None => None,
+    },
+    Data::Enum(variants) => {
+      let mut arms = String::new();
+      let mut has_source = false;
+      for variant in variants {
+        match variant.fields.source()? {
+          Some(index) => {
+            has_source = true;
+            let field = &variant.fields.all()[index];
+            arms.push_str(&format!(
+              "Self::{} {{ {}: __source, .. }} => {},",
+              variant.name,
+              source_member(field, index),
+              source_expr(field)
+            ));
+          }
+          None => arms.push_str(&format!(
+            "Self::{} {{ .. }} => ::core::option::Option::None,",
+            variant.name
+          )),
+        }
+      }
+      if has_source {
+        Some(format!("match self {{ {arms} }}"))
+      } else {
+        None
+      }
+    }
+  };
+  Ok(match body {
+    Some(body) => format!(
+      "#[automatically_derived] impl ::display_full_error::__private::Error for {} {{ fn source(&self) -> ::core::option::Option<&(dyn ::display_full_error::__private::Error + 'static)> {{ use ::display_full_error::__private::AsDynError as _; {} }} }}",
+      input.name, body
+    ),
+    None => format!("#[automatically_derived] impl ::display_full_error::__private::Error for {} {{}}", input.name),
+  })
+}
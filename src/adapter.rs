@@ -0,0 +1,371 @@
+//! Composable adapters, customizing the chain output layer by layer.
+
+use crate::fingerprint::Fnv1a;
+use crate::private::AsDynError;
+use crate::{chain_len, Chain, Error};
+use ::core::fmt::{self, Write};
+
+/// Layer customizing how an error chain is displayed.
+///
+/// Adapters are used with [`DisplayFullErrorAdapted`] and stacked with
+/// [`then`](Self::then), so each behavior is written once and combined as
+/// needed instead of providing a wrapper type for every combination. Every
+/// method has a default implementation leaving the output unchanged, so an
+/// adapter only implements the part it customizes.
+///
+/// This crate provides the [`Sanitize`], [`Truncate`], [`Colorize`],
+/// [`DedupMessages`] and [`RootFirst`] adapters.
+pub trait ChainAdapter {
+  /// Check if the error at `depth` is printed, `0` is the outermost error.
+  ///
+  /// `parent` is the previous error of the chain, whether it is printed or
+  /// not; it is `None` for the outermost error.
+  fn keep(&self, depth: usize, error: &(dyn Error + 'static), parent: Option<&(dyn Error + 'static)>) -> bool {
+    let _ = (depth, error, parent);
+    true
+  }
+
+  /// Write the message of the error at `depth`.
+  fn write_message(&self, f: &mut fmt::Formatter<'_>, depth: usize, message: &dyn fmt::Display) -> fmt::Result {
+    let _ = depth;
+    fmt::Display::fmt(message, f)
+  }
+
+  /// Check if the chain is printed in reverse order, starting with the root
+  /// cause.
+  fn reversed(&self) -> bool {
+    false
+  }
+
+  /// Stack `next` on top of this adapter.
+  ///
+  /// A message is kept if both adapters keep it. Messages are written by this
+  /// adapter first, and the result is written by `next`: for example, with
+  /// `Truncate(20).then(Colorize(style))` the escape codes are added around
+  /// the truncated message. Reversing the order twice restores it.
+  fn then<A>(self, next: A) -> Layered<Self, A>
+  where
+    Self: Sized,
+    A: ChainAdapter,
+  {
+    Layered(self, next)
+  }
+}
+
+/// Adapter stacking two adapters, see [`ChainAdapter::then`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Layered<A, B>(pub A, pub B);
+
+impl<A, B> ChainAdapter for Layered<A, B>
+where
+  A: ChainAdapter,
+  B: ChainAdapter,
+{
+  fn keep(&self, depth: usize, error: &(dyn Error + 'static), parent: Option<&(dyn Error + 'static)>) -> bool {
+    self.0.keep(depth, error, parent) && self.1.keep(depth, error, parent)
+  }
+
+  fn write_message(&self, f: &mut fmt::Formatter<'_>, depth: usize, message: &dyn fmt::Display) -> fmt::Result {
+    let inner = Adapted {
+      adapter: &self.0,
+      depth,
+      message,
+    };
+    self.1.write_message(f, depth, &inner)
+  }
+
+  fn reversed(&self) -> bool {
+    self.0.reversed() != self.1.reversed()
+  }
+}
+
+/// Message written by an adapter, to pass it to the next layer.
+struct Adapted<'a, A> {
+  adapter: &'a A,
+  depth: usize,
+  message: &'a dyn fmt::Display,
+}
+
+impl<A> fmt::Display for Adapted<'_, A>
+where
+  A: ChainAdapter,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.adapter.write_message(f, self.depth, self.message)
+  }
+}
+
+/// Adapter escaping the control characters, such as newlines, so each
+/// message stays on a single line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Sanitize;
+
+impl ChainAdapter for Sanitize {
+  fn write_message(&self, f: &mut fmt::Formatter<'_>, _depth: usize, message: &dyn fmt::Display) -> fmt::Result {
+    write!(SanitizeWriter(f), "{message}")
+  }
+}
+
+/// Writer escaping the control characters.
+struct SanitizeWriter<W>(W);
+
+impl<W> Write for SanitizeWriter<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, mut s: &str) -> fmt::Result {
+    while let Some(i) = s.find(char::is_control) {
+      let (before, rest) = s.split_at(i);
+      self.0.write_str(before)?;
+      let mut chars = rest.chars();
+      if let Some(c) = chars.next() {
+        write!(self.0, "{}", c.escape_default())?;
+      }
+      s = chars.as_str();
+    }
+    self.0.write_str(s)
+  }
+}
+
+/// Adapter truncating each message to the given number of chars.
+///
+/// Truncated messages end with `...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Truncate(pub usize);
+
+impl ChainAdapter for Truncate {
+  fn write_message(&self, f: &mut fmt::Formatter<'_>, _depth: usize, message: &dyn fmt::Display) -> fmt::Result {
+    let mut writer = TruncateWriter {
+      inner: &mut *f,
+      remaining: self.0,
+      cut: false,
+    };
+    write!(writer, "{message}")?;
+    if writer.cut {
+      f.write_str("...")?;
+    }
+    Ok(())
+  }
+}
+
+/// Writer forwarding at most `remaining` chars.
+struct TruncateWriter<W> {
+  inner: W,
+  remaining: usize,
+  cut: bool,
+}
+
+impl<W> Write for TruncateWriter<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    match s.char_indices().nth(self.remaining) {
+      Some((end, _)) => {
+        self.remaining = 0;
+        self.cut = true;
+        self.inner.write_str(s.get(..end).unwrap_or_default())
+      }
+      None => {
+        self.remaining = self.remaining.saturating_sub(s.chars().count());
+        self.inner.write_str(s)
+      }
+    }
+  }
+}
+
+/// Adapter wrapping each message in the ANSI style returned for its depth.
+///
+/// The function returns the escape sequence starting the style, such as
+/// `"\x1b[1;31m"` for bold red, or `None` to leave the message unstyled. The
+/// style is reset after the message.
+#[derive(Debug, Clone, Copy)]
+pub struct Colorize(pub fn(usize) -> Option<&'static str>);
+
+/// Escape sequence resetting the ANSI style.
+const RESET: &str = "\x1b[0m";
+
+impl ChainAdapter for Colorize {
+  fn write_message(&self, f: &mut fmt::Formatter<'_>, depth: usize, message: &dyn fmt::Display) -> fmt::Result {
+    match (self.0)(depth) {
+      Some(style) => write!(f, "{style}{message}{RESET}"),
+      None => fmt::Display::fmt(message, f),
+    }
+  }
+}
+
+/// Adapter skipping the messages identical to the message of their parent.
+///
+/// This collapses wrappers repeating the message of their source. Messages
+/// are compared by their 64-bit FNV-1a hash, so they are not buffered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DedupMessages;
+
+impl ChainAdapter for DedupMessages {
+  fn keep(&self, _depth: usize, error: &(dyn Error + 'static), parent: Option<&(dyn Error + 'static)>) -> bool {
+    parent.map_or(true, |parent| hash_message(error) != hash_message(parent))
+  }
+}
+
+/// Hash the message of `error`.
+fn hash_message(error: &(dyn Error + 'static)) -> u64 {
+  let mut hasher = Fnv1a::new();
+  let _ = write!(hasher, "{error}");
+  hasher.finish()
+}
+
+/// Adapter printing the chain starting with the root cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RootFirst;
+
+impl ChainAdapter for RootFirst {
+  fn reversed(&self) -> bool {
+    true
+  }
+}
+
+/// Formatting wrapper to display an error chain through a [`ChainAdapter`].
+///
+/// Error messages are formatted on a single line, separated with `: `. The
+/// chain is considered up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages,
+/// if it is longer a `...` is printed after the last message (or before the
+/// first one, in reverse order).
+///
+/// ```rust
+/// use ::display_full_error::{ChainAdapter, DisplayFullErrorExt, RootFirst, Sanitize, Truncate};
+///
+/// let err = ::std::io::Error::other("permission\ndenied");
+/// let adapter = Sanitize.then(Truncate(12)).then(RootFirst);
+/// assert_eq!(err.display_adapted(adapter).to_string(), "permission\\n...");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorAdapted<'e, E, A>(pub &'e E, pub A)
+where
+  E: Error + ?Sized;
+
+impl<E, A> fmt::Display for DisplayFullErrorAdapted<'_, E, A>
+where
+  E: Error + AsDynError + ?Sized,
+  A: ChainAdapter,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let root = self.0.as_dyn_error();
+    let adapter = &self.1;
+    let (len, truncated) = chain_len(root);
+    let mut writer = AdaptedWriter {
+      adapter,
+      first: !truncated || !adapter.reversed(),
+    };
+    if adapter.reversed() {
+      if truncated {
+        f.write_str("...")?;
+      }
+      // Each error is reached from the start of the chain, as sources can only
+      // be walked forward
+      for depth in (0..len).rev() {
+        let mut chain = Chain::new(root).skip(depth.saturating_sub(1));
+        let parent = if depth == 0 { None } else { chain.next() };
+        if let Some(error) = chain.next() {
+          writer.write(f, depth, error, parent)?;
+        }
+      }
+    } else {
+      let mut parent = None;
+      for (depth, error) in Chain::new(root).enumerate() {
+        writer.write(f, depth, error, parent)?;
+        parent = Some(error);
+      }
+      if truncated {
+        f.write_str(if writer.first { "..." } else { ": ..." })?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// State of a chain written through an adapter.
+struct AdaptedWriter<'a, A> {
+  adapter: &'a A,
+  /// Whether no message was written yet
+  first: bool,
+}
+
+impl<A> AdaptedWriter<'_, A>
+where
+  A: ChainAdapter,
+{
+  /// Write the error at `depth` if the adapter keeps it, with its separator.
+  fn write(
+    &mut self,
+    f: &mut fmt::Formatter<'_>,
+    depth: usize,
+    error: &(dyn Error + 'static),
+    parent: Option<&(dyn Error + 'static)>,
+  ) -> fmt::Result {
+    if !self.adapter.keep(depth, error, parent) {
+      return Ok(());
+    }
+    if !::core::mem::take(&mut self.first) {
+      f.write_str(": ")?;
+    }
+    self.adapter.write_message(f, depth, &error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ChainAdapter, Colorize, DedupMessages, RootFirst, Sanitize, Truncate};
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  fn bold_root(depth: usize) -> Option<&'static str> {
+    (depth == 0).then_some("\x1b[1m")
+  }
+
+  #[test]
+  fn single_adapters() {
+    let input = message_chain(&[
+      "upload failed",
+      "request failed",
+      "request failed",
+      "permission\tdenied",
+    ]);
+    let actual: String = input.display_adapted(Sanitize).to_string();
+    let expected = String::from("upload failed: request failed: request failed: permission\\tdenied");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_adapted(DedupMessages).to_string();
+    let expected = String::from("upload failed: request failed: permission\tdenied");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_adapted(Truncate(7)).to_string();
+    let expected = String::from("upload ...: request...: request...: permiss...");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_adapted(RootFirst).to_string();
+    let expected = String::from("permission\tdenied: request failed: request failed: upload failed");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_adapted(Colorize(bold_root)).to_string();
+    let expected = String::from("\x1b[1mupload failed\x1b[0m: request failed: request failed: permission\tdenied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn stacked_adapters() {
+    let input = message_chain(&[
+      "upload failed",
+      "request failed",
+      "request failed",
+      "permission\ndenied",
+    ]);
+    let adapter = Sanitize
+      .then(Truncate(12))
+      .then(DedupMessages)
+      .then(RootFirst)
+      .then(Colorize(bold_root));
+    let actual: String = input.display_adapted(adapter).to_string();
+    let expected = String::from("permission\\n...: request fail...: \x1b[1mupload faile...\x1b[0m");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_adapted(RootFirst.then(RootFirst)).to_string();
+    let expected = String::from("upload failed: request failed: request failed: permission\ndenied");
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,74 @@
+//! ASCII-only output mode, for sinks mangling UTF-8.
+
+use crate::{DisplayFullError, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the full error chain with ASCII chars only.
+///
+/// The output is the same as [`DisplayFullError`], with every non-ASCII char
+/// escaped as `\u{...}` with its hexadecimal code point, as in Rust string
+/// literals. This keeps the output readable in legacy log sinks and serial
+/// consoles which mangle UTF-8. ASCII chars, including backslashes, are
+/// written as-is.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("accès refusé");
+/// assert_eq!(err.display_ascii().to_string(), "acc\\u{e8}s refus\\u{e9}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorAscii<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorAscii<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(AsciiEscape(f), "{}", DisplayFullError(self.0))
+  }
+}
+
+/// Writer escaping the non-ASCII chars written to the inner writer.
+struct AsciiEscape<W>(W);
+
+impl<W> Write for AsciiEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() {
+      if c.is_ascii() {
+        self.0.write_char(c)?;
+      } else {
+        write!(self.0, "{}", c.escape_unicode())?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn ascii_chain_is_unchanged() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_ascii().to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn ascii_escapes_non_ascii() {
+    let input = message_chain(&["échec", "fichier 📄 introuvable"]);
+    let actual: String = input.display_ascii().to_string();
+    let expected = String::from("\\u{e9}chec: fichier \\u{1f4c4} introuvable");
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,196 @@
+//! Reporter forwarding owned snapshots to a dedicated thread.
+
+use crate::{chain_len, Chain, Error, ErrorReporter};
+use ::core::fmt;
+use ::core::sync::atomic::{AtomicU64, Ordering};
+use ::std::boxed::Box;
+use ::std::io;
+use ::std::string::{String, ToString};
+use ::std::sync::mpsc::{sync_channel, SyncSender};
+use ::std::thread::{Builder, JoinHandle};
+use ::std::vec::Vec;
+
+/// Owned copy of the messages of an error chain.
+///
+/// The snapshot implements `Error`, with a source for each source of the
+/// original chain, so it is formatted exactly as the original error. Only the
+/// messages are kept: the snapshot can't be downcast to the original types.
+/// The chain is copied up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT)
+/// messages.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{DisplayFullErrorExt, ErrorSnapshot};
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// let snapshot = ErrorSnapshot::new(&err);
+/// assert_eq!(snapshot.to_string_full(), err.to_string_full());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorSnapshot {
+  message: String,
+  source: Option<Box<ErrorSnapshot>>,
+}
+
+impl ErrorSnapshot {
+  /// Copy the messages of the chain of `error`.
+  pub fn new(error: &(dyn Error + 'static)) -> Self {
+    let mut messages: Vec<String> = Chain::new(error).map(|e| e.to_string()).collect();
+    if chain_len(error).1 {
+      // Formatting the snapshot then prints the same `: ...` as the original
+      messages.push(String::from("..."));
+    }
+    let mut snapshot = None;
+    for message in messages.into_iter().rev() {
+      snapshot = Some(Box::new(Self {
+        message,
+        source: snapshot,
+      }));
+    }
+    // The chain always contains the error itself
+    snapshot.map_or_else(
+      || Self {
+        message: String::new(),
+        source: None,
+      },
+      |snapshot| *snapshot,
+    )
+  }
+
+  /// Get the message of the outermost error.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl fmt::Display for ErrorSnapshot {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+impl Error for ErrorSnapshot {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    self.source.as_deref().map(|source| source as &(dyn Error + 'static))
+  }
+}
+
+/// Reporter forwarding the errors to an inner reporter running on a dedicated
+/// thread.
+///
+/// Each reported error is copied into an [`ErrorSnapshot`] and sent over a
+/// bounded channel with `capacity` slots. The formatting of the full chain and
+/// the IO of the inner reporter happen on the reporting thread, so
+/// latency-sensitive paths only pay for copying the messages. Reporting never
+/// blocks: when the channel is full, the error is dropped and counted in
+/// [`dropped`](Self::dropped).
+///
+/// Dropping the reporter waits for the pending errors to be reported.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{BackgroundReporter, ErrorReporter, StderrReporter};
+///
+/// let reporter = BackgroundReporter::new(StderrReporter, 1024).expect("failed to spawn the reporting thread");
+/// reporter.report(&::std::io::Error::other("permission denied"));
+/// ```
+#[derive(Debug)]
+pub struct BackgroundReporter<R> {
+  sender: Option<SyncSender<ErrorSnapshot>>,
+  thread: Option<JoinHandle<R>>,
+  dropped: AtomicU64,
+}
+
+impl<R> BackgroundReporter<R>
+where
+  R: ErrorReporter + 'static,
+{
+  /// Spawn the reporting thread, forwarding to `inner`, with a channel of
+  /// `capacity` errors.
+  pub fn new(inner: R, capacity: usize) -> io::Result<Self> {
+    let (sender, receiver) = sync_channel::<ErrorSnapshot>(capacity);
+    let thread = Builder::new().name(String::from("error-reporter")).spawn(move || {
+      for snapshot in receiver {
+        inner.report(&snapshot);
+      }
+      inner
+    })?;
+    Ok(Self {
+      sender: Some(sender),
+      thread: Some(thread),
+      dropped: AtomicU64::new(0),
+    })
+  }
+
+  /// Get the number of errors dropped because the channel was full.
+  pub fn dropped(&self) -> u64 {
+    self.dropped.load(Ordering::Relaxed)
+  }
+
+  /// Wait for the pending errors to be reported, and return the inner
+  /// reporter.
+  ///
+  /// Returns `None` if the reporting thread panicked.
+  pub fn into_inner(mut self) -> Option<R> {
+    self.shutdown()
+  }
+
+  /// Close the channel and wait for the reporting thread.
+  fn shutdown(&mut self) -> Option<R> {
+    drop(self.sender.take());
+    self.thread.take()?.join().ok()
+  }
+}
+
+impl<R> ErrorReporter for BackgroundReporter<R>
+where
+  R: ErrorReporter + 'static,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    let Some(sender) = &self.sender else {
+      return;
+    };
+    // The channel is only disconnected if the reporting thread panicked
+    if sender.try_send(ErrorSnapshot::new(error)).is_err() {
+      self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+}
+
+impl<R> Drop for BackgroundReporter<R> {
+  fn drop(&mut self) {
+    drop(self.sender.take());
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{BackgroundReporter, ErrorSnapshot};
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::{DisplayFullErrorExt, ErrorReporter, WriteReporter};
+  use ::alloc::vec::Vec;
+
+  #[test]
+  fn snapshot_same_output() {
+    let input = UploadError::Permission(PermissionError);
+    let snapshot = ErrorSnapshot::new(&input);
+    assert_eq!(snapshot.message(), "upload failed");
+    assert_eq!(snapshot.to_string_full(), input.to_string_full());
+    assert_eq!(snapshot.fingerprint(), input.fingerprint());
+  }
+
+  #[test]
+  fn report_on_thread() {
+    let reporter = BackgroundReporter::new(WriteReporter::new(Vec::new()), 16).expect("thread is spawned");
+    reporter.report(&message_chain(&["upload failed", "permission denied"]));
+    reporter.report(&message_chain(&["job failed"]));
+    assert_eq!(reporter.dropped(), 0);
+    let inner = reporter.into_inner().map(WriteReporter::into_inner);
+    assert_eq!(inner, Some(b"upload failed: permission denied\njob failed\n".to_vec()));
+  }
+}
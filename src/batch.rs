@@ -0,0 +1,191 @@
+//! Reporter buffering the errors and forwarding them in batches.
+
+use crate::{Error, ErrorReporter, ErrorSnapshot};
+use ::std::sync::{Mutex, PoisonError};
+use ::std::time::{Duration, Instant};
+use ::std::vec::Vec;
+
+/// Reporter buffering the errors and forwarding them to an inner reporter in
+/// batches.
+///
+/// Each reported error is copied into an [`ErrorSnapshot`]. The batch is
+/// forwarded with [`report_batch`](ErrorReporter::report_batch) once it holds
+/// `max_batch` errors, or on the first report after `interval` has elapsed
+/// since the batch started. Call [`flush`](Self::flush) periodically to also
+/// forward the last errors of a quiet stream. [`WriteReporter`](crate::WriteReporter)
+/// and [`NdjsonReporter`](crate::NdjsonReporter) flush their output once per
+/// batch, reducing the syscall overhead of high-volume error streams.
+///
+/// Dropping the reporter forwards the pending errors.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::std::time::Duration;
+/// use ::display_full_error::{BatchingReporter, ErrorReporter, StderrReporter};
+///
+/// let reporter = BatchingReporter::new(StderrReporter, 100, Duration::from_secs(1));
+/// reporter.report(&::std::io::Error::other("connection refused"));
+/// // Call periodically, e.g. from a background task
+/// reporter.flush();
+/// ```
+#[derive(Debug)]
+pub struct BatchingReporter<R>
+where
+  R: ErrorReporter,
+{
+  inner: R,
+  max_batch: usize,
+  interval: Duration,
+  batch: Mutex<Batch>,
+}
+
+/// Errors buffered since the start of the batch.
+#[derive(Debug)]
+struct Batch {
+  start: Instant,
+  snapshots: Vec<ErrorSnapshot>,
+}
+
+impl<R> BatchingReporter<R>
+where
+  R: ErrorReporter,
+{
+  /// Create a new reporter forwarding to `inner` batches of at most
+  /// `max_batch` errors, at least once per `interval` while errors are
+  /// reported.
+  pub fn new(inner: R, max_batch: usize, interval: Duration) -> Self {
+    Self {
+      inner,
+      max_batch,
+      interval,
+      batch: Mutex::new(Batch {
+        start: Instant::now(),
+        snapshots: Vec::new(),
+      }),
+    }
+  }
+
+  /// Get a reference to the inner reporter.
+  pub fn inner(&self) -> &R {
+    &self.inner
+  }
+
+  /// Forward the pending errors.
+  pub fn flush(&self) {
+    let snapshots = self.take_batch(Instant::now());
+    self.forward(&snapshots);
+  }
+
+  /// Report `error` as if the current time was `now`.
+  fn report_at(&self, error: &(dyn Error + 'static), now: Instant) {
+    let snapshot = ErrorSnapshot::new(error);
+    let snapshots = {
+      let mut batch = self.batch.lock().unwrap_or_else(PoisonError::into_inner);
+      if batch.snapshots.is_empty() {
+        batch.start = now;
+      }
+      batch.snapshots.push(snapshot);
+      let full = batch.snapshots.len() >= self.max_batch;
+      if !full && now.saturating_duration_since(batch.start) < self.interval {
+        return;
+      }
+      batch.start = now;
+      ::core::mem::take(&mut batch.snapshots)
+    };
+    self.forward(&snapshots);
+  }
+
+  /// Take the pending errors, starting a new batch at `now`.
+  fn take_batch(&self, now: Instant) -> Vec<ErrorSnapshot> {
+    let mut batch = self.batch.lock().unwrap_or_else(PoisonError::into_inner);
+    batch.start = now;
+    ::core::mem::take(&mut batch.snapshots)
+  }
+
+  /// Forward a batch to the inner reporter.
+  fn forward(&self, snapshots: &[ErrorSnapshot]) {
+    if snapshots.is_empty() {
+      return;
+    }
+    let errors: Vec<&(dyn Error + 'static)> = snapshots.iter().map(|s| s as &(dyn Error + 'static)).collect();
+    self.inner.report_batch(&errors);
+  }
+}
+
+impl<R> ErrorReporter for BatchingReporter<R>
+where
+  R: ErrorReporter,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.report_at(error, Instant::now());
+  }
+}
+
+impl<R> Drop for BatchingReporter<R>
+where
+  R: ErrorReporter,
+{
+  fn drop(&mut self) {
+    self.flush();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::BatchingReporter;
+  use crate::tests::message_chain;
+  use crate::{Error, ErrorReporter, FnReporter};
+  use ::core::sync::atomic::{AtomicUsize, Ordering};
+  use ::std::time::{Duration, Instant};
+
+  /// Reporter counting the batches and the errors.
+  #[derive(Default)]
+  struct CountingReporter {
+    batches: AtomicUsize,
+    errors: AtomicUsize,
+  }
+
+  impl ErrorReporter for CountingReporter {
+    fn report(&self, _error: &(dyn Error + 'static)) {
+      self.report_batch(&[]);
+    }
+
+    fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+      self.batches.fetch_add(1, Ordering::Relaxed);
+      self.errors.fetch_add(errors.len(), Ordering::Relaxed);
+    }
+  }
+
+  #[test]
+  fn batch_on_size_and_interval() {
+    let reporter = BatchingReporter::new(CountingReporter::default(), 3, Duration::from_secs(1));
+    let start = Instant::now();
+    let error = message_chain(&["upload failed", "permission denied"]);
+    for _ in 0..7 {
+      reporter.report_at(&error, start);
+    }
+    assert_eq!(reporter.inner().batches.load(Ordering::Relaxed), 2);
+    reporter.report_at(&error, start + Duration::from_secs(1));
+    assert_eq!(reporter.inner().batches.load(Ordering::Relaxed), 3);
+    assert_eq!(reporter.inner().errors.load(Ordering::Relaxed), 8);
+    reporter.report_at(&error, start + Duration::from_secs(1));
+    reporter.flush();
+    reporter.flush();
+    assert_eq!(reporter.inner().batches.load(Ordering::Relaxed), 4);
+    assert_eq!(reporter.inner().errors.load(Ordering::Relaxed), 9);
+  }
+
+  #[test]
+  fn flush_on_drop() {
+    let count = AtomicUsize::new(0);
+    let counter = |_: &(dyn Error + 'static)| {
+      count.fetch_add(1, Ordering::Relaxed);
+    };
+    let reporter = BatchingReporter::new(FnReporter(counter), 10, Duration::from_secs(1));
+    reporter.report(&message_chain(&["job failed"]));
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+    drop(reporter);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+  }
+}
@@ -0,0 +1,59 @@
+//! Thread-local buffer reused when formatting chains to strings.
+
+use crate::{DisplayFullError, Error};
+use ::core::cell::RefCell;
+use ::core::fmt::Write;
+use ::std::string::{String, ToString};
+
+/// Maximum capacity kept by the buffer between calls, in bytes.
+///
+/// Larger buffers are released after use, to avoid keeping memory for a
+/// single unusually long chain.
+const MAX_RETAINED_CAPACITY: usize = 16 * 1024;
+
+::std::thread_local! {
+  static BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Format the full chain of `error` in the thread-local buffer, and return a
+/// copy with an exact capacity.
+pub(crate) fn to_string_full_cached<E>(error: &E) -> String
+where
+  E: Error + ?Sized,
+{
+  BUFFER
+    .try_with(|buffer| {
+      // The buffer is already borrowed if an error formats a chain while
+      // being formatted, fall back to a fresh string.
+      let mut buffer = buffer.try_borrow_mut().ok()?;
+      buffer.clear();
+      // Writing to a string never fails
+      let _ = write!(buffer, "{}", DisplayFullError(error));
+      let message = String::from(buffer.as_str());
+      if buffer.capacity() > MAX_RETAINED_CAPACITY {
+        *buffer = String::new();
+      }
+      Some(message)
+    })
+    .ok()
+    .flatten()
+    .unwrap_or_else(|| DisplayFullError(error).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::String;
+
+  #[test]
+  fn cached_matches_to_string_full() {
+    let first = message_chain(&["upload failed", "permission denied"]);
+    let second = message_chain(&["job failed"]);
+    assert_eq!(
+      first.to_string_full_cached(),
+      String::from("upload failed: permission denied")
+    );
+    assert_eq!(second.to_string_full_cached(), String::from("job failed"));
+  }
+}
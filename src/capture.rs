@@ -0,0 +1,235 @@
+//! Opt-in capture of the last reported error chain.
+//!
+//! The chain is stored in a fixed-size buffer of atomics guarded by a sequence
+//! lock, so [`last_error`] never blocks nor allocates and can be called from
+//! abort or signal handlers.
+
+use crate::fingerprint::fingerprint;
+use crate::private::AsDynError;
+use crate::DisplayFullError;
+use ::core::fmt::{self, Write};
+use ::core::str;
+use ::core::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use ::core::time::Duration;
+use ::std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum length of the recorded message, in bytes.
+///
+/// Longer chains are truncated on a `char` boundary.
+pub const LAST_ERROR_CAPACITY: usize = 1024;
+
+/// Number of attempts of [`last_error`] to read a consistent record before
+/// giving up, if writers keep updating it.
+const READ_ATTEMPTS: usize = 64;
+
+/// Whether [`report`](crate::report) records the chain.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Sequence number of the record: odd while a write is in progress.
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU8 = AtomicU8::new(0);
+
+/// Bytes of the recorded message.
+static MESSAGE: [AtomicU8; LAST_ERROR_CAPACITY] = [ZERO; LAST_ERROR_CAPACITY];
+
+/// Length of the recorded message.
+static MESSAGE_LEN: AtomicUsize = AtomicUsize::new(0);
+
+/// Fingerprint of the recorded chain.
+static FINGERPRINT: AtomicU64 = AtomicU64::new(0);
+
+/// Time of the record, in nanoseconds since the Unix epoch.
+static TIMESTAMP: AtomicU64 = AtomicU64::new(0);
+
+/// Error chain recorded by the last error capture hook.
+///
+/// See [`record_last_error`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LastError {
+  message: [u8; LAST_ERROR_CAPACITY],
+  len: usize,
+  /// [Fingerprint](crate::DisplayFullErrorExt::fingerprint) of the chain.
+  pub fingerprint: u64,
+  /// Time when the chain was recorded.
+  pub timestamp: SystemTime,
+}
+
+impl LastError {
+  /// Full chain, as formatted by [`DisplayFullError`](struct@DisplayFullError),
+  /// truncated to [`LAST_ERROR_CAPACITY`] bytes.
+  pub fn message(&self) -> &str {
+    let bytes = self.message.get(..self.len).unwrap_or_default();
+    // The buffer is only written with whole chars
+    str::from_utf8(bytes).unwrap_or_default()
+  }
+}
+
+impl fmt::Debug for LastError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("LastError")
+      .field("message", &self.message())
+      .field("fingerprint", &self.fingerprint)
+      .field("timestamp", &self.timestamp)
+      .finish()
+  }
+}
+
+/// Enable or disable the last error capture hook of [`report`](crate::report).
+///
+/// When enabled, each error passed to [`report`](crate::report) is recorded
+/// with [`record_last_error`]. The hook is disabled by default, as it formats
+/// each chain a second time.
+///
+/// Requires the `std` feature.
+pub fn set_last_error_capture(enabled: bool) {
+  ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the last error capture hook of [`report`](crate::report) is
+/// enabled.
+pub(crate) fn capture_enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record the chain of `error` in the global slot read by [`last_error`].
+///
+/// This allows abort handlers and watchdogs to report the last known error.
+/// If another thread is recording a chain at the same time, this record is
+/// skipped.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{last_error, record_last_error};
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// record_last_error(&err);
+/// assert_eq!(last_error().as_ref().map(|e| e.message()), Some("permission denied"));
+/// ```
+pub fn record_last_error<E>(error: &E)
+where
+  E: AsDynError + ?Sized,
+{
+  let error = error.as_dyn_error();
+  // Format before taking the lock, so a panicking `Display` implementation
+  // doesn't leave the record locked.
+  let mut buffer = Buffer {
+    bytes: [0; LAST_ERROR_CAPACITY],
+    len: 0,
+  };
+  let _ = write!(buffer, "{}", DisplayFullError(error));
+  let fingerprint = fingerprint(error);
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+
+  let sequence = SEQUENCE.load(Ordering::Relaxed);
+  if sequence % 2 == 1
+    || SEQUENCE
+      .compare_exchange(sequence, sequence.wrapping_add(1), Ordering::Acquire, Ordering::Relaxed)
+      .is_err()
+  {
+    return;
+  }
+  fence(Ordering::Release);
+  for (slot, &byte) in MESSAGE.iter().zip(buffer.bytes.iter()) {
+    slot.store(byte, Ordering::Relaxed);
+  }
+  MESSAGE_LEN.store(buffer.len, Ordering::Relaxed);
+  FINGERPRINT.store(fingerprint, Ordering::Relaxed);
+  TIMESTAMP.store(timestamp, Ordering::Relaxed);
+  SEQUENCE.store(sequence.wrapping_add(2), Ordering::Release);
+}
+
+/// Get the last error chain recorded with [`record_last_error`], if any.
+///
+/// This function only reads atomics: it never blocks nor allocates, so it is
+/// safe to call from an abort or signal handler. It returns `None` if no chain
+/// was recorded, or if a consistent record could not be read because a write
+/// was in progress.
+///
+/// Requires the `std` feature.
+pub fn last_error() -> Option<LastError> {
+  for _ in 0..READ_ATTEMPTS {
+    let before = SEQUENCE.load(Ordering::Acquire);
+    if before == 0 {
+      return None;
+    }
+    if before % 2 == 1 {
+      ::core::hint::spin_loop();
+      continue;
+    }
+    let mut message = [0; LAST_ERROR_CAPACITY];
+    for (byte, slot) in message.iter_mut().zip(MESSAGE.iter()) {
+      *byte = slot.load(Ordering::Relaxed);
+    }
+    let len = MESSAGE_LEN.load(Ordering::Relaxed);
+    let fingerprint = FINGERPRINT.load(Ordering::Relaxed);
+    let timestamp = TIMESTAMP.load(Ordering::Relaxed);
+    fence(Ordering::Acquire);
+    if SEQUENCE.load(Ordering::Relaxed) == before {
+      return Some(LastError {
+        message,
+        len: len.min(LAST_ERROR_CAPACITY),
+        fingerprint,
+        timestamp: UNIX_EPOCH
+          .checked_add(Duration::from_nanos(timestamp))
+          .unwrap_or(UNIX_EPOCH),
+      });
+    }
+  }
+  None
+}
+
+/// Fixed-size output, dropping the chars past its capacity.
+struct Buffer {
+  bytes: [u8; LAST_ERROR_CAPACITY],
+  len: usize,
+}
+
+impl Write for Buffer {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() {
+      let end = self.len.saturating_add(c.len_utf8());
+      match self.bytes.get_mut(self.len..end) {
+        Some(dest) => {
+          c.encode_utf8(dest);
+          self.len = end;
+        }
+        None => return Ok(()),
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{last_error, record_last_error, LAST_ERROR_CAPACITY};
+  use crate::fingerprint::fingerprint;
+  use crate::tests::message_chain;
+  use ::alloc::boxed::Box;
+
+  // The record is global, so all the assertions are in a single test to avoid
+  // interference between parallel tests.
+  #[test]
+  fn record_and_read() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    record_last_error(&input);
+    let last = last_error();
+    assert_eq!(
+      last.as_ref().map(|e| e.message()),
+      Some("upload failed: permission denied")
+    );
+    assert_eq!(last.map(|e| e.fingerprint), Some(fingerprint(&input)));
+
+    let long: &'static str = Box::leak("é".repeat(LAST_ERROR_CAPACITY).into_boxed_str());
+    record_last_error(&message_chain(&[long]));
+    let last = last_error();
+    let message = last.as_ref().map(|e| e.message()).unwrap_or_default();
+    assert_eq!(message.len(), LAST_ERROR_CAPACITY);
+    assert!(long.starts_with(message));
+  }
+}
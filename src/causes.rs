@@ -0,0 +1,66 @@
+//! Causes display mode, printing only the sources of the error.
+
+use crate::{sources, Error, MESSAGE_LIMIT};
+use ::core::fmt;
+
+/// Formatting wrapper to display only the sources of an error, without its own
+/// message.
+///
+/// Messages are formatted on a single line, separated with `: `. If the error
+/// has no source, the output is empty. The chain is printed up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages (including the omitted
+/// outermost error), if it is longer the output ends with `: ...` as with
+/// [`DisplayFullError`](struct@crate::DisplayFullError).
+///
+/// Combined with the `Display` implementation of the error itself, this
+/// matches structured log schemas with distinct `error` and `error.cause`
+/// fields. See [`DisplayFullErrorExt::display_split`](crate::DisplayFullErrorExt::display_split).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorCauses<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorCauses<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut printed: u16 = 1;
+    for e in sources(self.0) {
+      if printed >= MESSAGE_LIMIT {
+        f.write_str(": ...")?;
+        return Ok(());
+      }
+      if printed > 1 {
+        f.write_str(": ")?;
+      }
+      fmt::Display::fmt(e, f)?;
+      printed = printed.saturating_add(1);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn split_chain() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let (message, causes) = input.display_split();
+    assert_eq!(message.to_string(), String::from("upload failed"));
+    assert_eq!(causes.to_string(), String::from("connection reset: permission denied"));
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn split_without_source() {
+    let input = message_chain(&["upload failed"]);
+    let (message, causes) = input.to_string_split();
+    assert_eq!(message, String::from("upload failed"));
+    assert_eq!(causes, String::new());
+  }
+}
@@ -0,0 +1,95 @@
+//! Iteration over the errors of a chain, as trait objects.
+
+use crate::private::AsDynError;
+use crate::{DisplayFullError, Error, MESSAGE_LIMIT};
+use ::core::iter::FusedIterator;
+
+/// Iterator over the errors of a chain, starting with the error itself.
+///
+/// Up to [`MESSAGE_LIMIT`] errors are yielded, matching the messages printed
+/// by [`DisplayFullError`](struct@crate::DisplayFullError). Since the errors
+/// are yielded as `&(dyn Error + 'static)`, they can be downcast to inspect the
+/// chain programmatically.
+///
+/// It is also the iterator of a borrowed
+/// [`DisplayFullError`](struct@crate::DisplayFullError), so the wrapper can be
+/// used directly in a `for` loop.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// for e in &err.display_full() {
+///   assert_eq!(e.to_string(), "permission denied");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Chain<'e> {
+  next: Option<&'e (dyn Error + 'static)>,
+  remaining: u16,
+}
+
+impl<'e> Chain<'e> {
+  /// Create an iterator over the chain starting at `error`.
+  pub fn new(error: &'e (dyn Error + 'static)) -> Self {
+    Self {
+      next: Some(error),
+      remaining: MESSAGE_LIMIT,
+    }
+  }
+}
+
+impl<'e> Iterator for Chain<'e> {
+  type Item = &'e (dyn Error + 'static);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+    let error = self.next?;
+    self.next = error.source();
+    self.remaining = self.remaining.saturating_sub(1);
+    Some(error)
+  }
+}
+
+impl FusedIterator for Chain<'_> {}
+
+impl<'e, E> IntoIterator for &DisplayFullError<'e, E>
+where
+  E: Error + AsDynError + ?Sized,
+{
+  type Item = &'e (dyn Error + 'static);
+  type IntoIter = Chain<'e>;
+
+  fn into_iter(self) -> Chain<'e> {
+    Chain::new(self.0.as_dyn_error())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+
+  #[test]
+  fn chain_includes_error() {
+    let input = UploadError::Permission(PermissionError);
+    let mut chain = input.chain();
+    assert!(chain.next().is_some_and(|e| e.is::<UploadError>()));
+    assert!(chain.next().is_some_and(|e| e.is::<PermissionError>()));
+    assert!(chain.next().is_none());
+  }
+
+  #[test]
+  fn iterate_display_full() {
+    let input = UploadError::Permission(PermissionError);
+    let wrapper = input.display_full();
+    let mut count = 0;
+    for e in &wrapper {
+      assert_eq!(e.is::<UploadError>(), count == 0);
+      count += 1;
+    }
+    assert_eq!(count, 2);
+  }
+}
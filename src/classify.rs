@@ -0,0 +1,196 @@
+//! Classification of chains into coarse categories, for metrics labels.
+
+use crate::{Chain, Error};
+use ::core::fmt;
+
+/// Coarse category of an error chain, see [`ClassifyChain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorCategory {
+  /// Input/output failure.
+  Io,
+  /// Invalid input, such as a malformed number or invalid UTF-8.
+  Parse,
+  /// Operation which did not complete in time.
+  Timeout,
+  /// Operation denied for lack of permissions.
+  Permission,
+  /// Any other error.
+  Other,
+}
+
+impl ErrorCategory {
+  /// Get the name of the category, in lowercase: `io`, `parse`, `timeout`,
+  /// `permission` or `other`.
+  ///
+  /// The names are stable, so they can be used as metrics labels.
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      ErrorCategory::Io => "io",
+      ErrorCategory::Parse => "parse",
+      ErrorCategory::Timeout => "timeout",
+      ErrorCategory::Permission => "permission",
+      ErrorCategory::Other => "other",
+    }
+  }
+}
+
+impl fmt::Display for ErrorCategory {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// Classifier mapping error chains to an [`ErrorCategory`].
+///
+/// Each error of the chain is probed with [`classify`](ClassifyChain::classify),
+/// and the deepest classified error wins, as the root cause is the most
+/// specific. The default `classify` recognizes the parse errors of `core` and
+/// `alloc`, and the I/O errors of `std` by their kind. Override it to
+/// recognize your own error types, falling back to [`DefaultClassifier`]:
+///
+/// ```rust
+/// use ::display_full_error::{ClassifyChain, DefaultClassifier, ErrorCategory};
+/// use ::std::error::Error;
+///
+/// #[derive(Debug)]
+/// struct DeadlineExceeded;
+///
+/// impl ::std::fmt::Display for DeadlineExceeded {
+///   fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+///     f.write_str("deadline exceeded")
+///   }
+/// }
+///
+/// impl Error for DeadlineExceeded {}
+///
+/// struct AppClassifier;
+///
+/// impl ClassifyChain for AppClassifier {
+///   fn classify(&self, error: &(dyn Error + 'static)) -> Option<ErrorCategory> {
+///     if error.is::<DeadlineExceeded>() {
+///       return Some(ErrorCategory::Timeout);
+///     }
+///     DefaultClassifier.classify(error)
+///   }
+/// }
+///
+/// assert_eq!(AppClassifier.classify_chain(&DeadlineExceeded), ErrorCategory::Timeout);
+/// assert_eq!(DefaultClassifier.classify_chain(&DeadlineExceeded), ErrorCategory::Other);
+/// ```
+pub trait ClassifyChain {
+  /// Classify a single error of the chain, ignoring its sources.
+  ///
+  /// Returns `None` if the error is not recognized.
+  fn classify(&self, error: &(dyn Error + 'static)) -> Option<ErrorCategory> {
+    classify_default(error)
+  }
+
+  /// Classify a chain, as the category of its deepest classified error.
+  ///
+  /// Returns [`ErrorCategory::Other`] if no error of the chain is
+  /// recognized.
+  fn classify_chain(&self, error: &(dyn Error + 'static)) -> ErrorCategory {
+    Chain::new(error)
+      .filter_map(|e| self.classify(e))
+      .last()
+      .unwrap_or(ErrorCategory::Other)
+  }
+}
+
+/// Classifier using the default implementation of [`ClassifyChain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DefaultClassifier;
+
+impl ClassifyChain for DefaultClassifier {}
+
+/// Classify the errors of `core`, `alloc` and `std`.
+fn classify_default(error: &(dyn Error + 'static)) -> Option<ErrorCategory> {
+  if error.is::<::core::num::ParseIntError>()
+    || error.is::<::core::num::ParseFloatError>()
+    || error.is::<::core::str::ParseBoolError>()
+    || error.is::<::core::str::Utf8Error>()
+    || error.is::<::core::char::ParseCharError>()
+  {
+    return Some(ErrorCategory::Parse);
+  }
+  #[cfg(feature = "alloc")]
+  if error.is::<::alloc::string::FromUtf8Error>() || error.is::<::alloc::string::FromUtf16Error>() {
+    return Some(ErrorCategory::Parse);
+  }
+  #[cfg(feature = "std")]
+  if error.is::<::std::net::AddrParseError>() {
+    return Some(ErrorCategory::Parse);
+  }
+  #[cfg(feature = "std")]
+  if let Some(error) = error.downcast_ref::<::std::io::Error>() {
+    use ::std::io::ErrorKind;
+    return Some(match error.kind() {
+      ErrorKind::PermissionDenied => ErrorCategory::Permission,
+      ErrorKind::TimedOut => ErrorCategory::Timeout,
+      ErrorKind::InvalidData => ErrorCategory::Parse,
+      _ => ErrorCategory::Io,
+    });
+  }
+  #[cfg(feature = "std")]
+  if error.is::<::std::sync::mpsc::RecvTimeoutError>() {
+    return Some(ErrorCategory::Timeout);
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ClassifyChain, DefaultClassifier, ErrorCategory};
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::Error;
+  use ::core::fmt;
+
+  #[test]
+  fn classify_unknown_chain() {
+    let input = UploadError::Permission(PermissionError);
+    assert_eq!(DefaultClassifier.classify_chain(&input), ErrorCategory::Other);
+    assert_eq!(
+      DefaultClassifier.classify_chain(&message_chain(&["a", "b"])),
+      ErrorCategory::Other
+    );
+  }
+
+  #[test]
+  fn classify_parse_error() {
+    let input = "x".parse::<u8>().err();
+    let category = input.map(|e| DefaultClassifier.classify_chain(&e));
+    assert_eq!(category, Some(ErrorCategory::Parse));
+    assert_eq!(ErrorCategory::Parse.as_str(), "parse");
+  }
+
+  #[test]
+  fn classify_deepest_error() {
+    #[derive(Debug)]
+    struct ConfigError(::core::num::ParseIntError);
+
+    impl fmt::Display for ConfigError {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid configuration")
+      }
+    }
+
+    impl Error for ConfigError {
+      fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+      }
+    }
+
+    let input = "x".parse::<u8>().err().map(ConfigError);
+    let category = input.map(|e| DefaultClassifier.classify_chain(&e));
+    assert_eq!(category, Some(ErrorCategory::Parse));
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn classify_io_error() {
+    let input = ::std::io::Error::from(::std::io::ErrorKind::PermissionDenied);
+    assert_eq!(DefaultClassifier.classify_chain(&input), ErrorCategory::Permission);
+    let input = ::std::io::Error::other("disk full");
+    assert_eq!(DefaultClassifier.classify_chain(&input), ErrorCategory::Io);
+  }
+}
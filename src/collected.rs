@@ -0,0 +1,97 @@
+//! Chain collected in a vector, for random access and reverse traversal.
+
+use crate::{Chain, Error};
+use ::alloc::vec::Vec;
+use ::core::ops::Deref;
+
+/// Errors of a chain collected in a vector, starting with the error itself.
+///
+/// Walking the sources with [`source`](core::error::Error::source) is lazy and
+/// only goes forward. This collects the chain once, so it can be indexed and
+/// iterated in both directions. It dereferences to a slice of the errors, as
+/// `&(dyn Error + 'static)`. Like [`Chain`], it holds up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) errors.
+///
+/// Requires the `alloc` feature.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::new(::std::io::ErrorKind::Other, ::std::fmt::Error);
+/// let chain = err.collect_chain();
+/// assert_eq!(chain.len(), 1);
+/// assert!(chain[0].is::<::std::io::Error>());
+/// let root_first: Vec<String> = chain.iter().rev().map(|e| e.to_string()).collect();
+/// assert_eq!(root_first, ["an error occurred when formatting an argument"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CollectedChain<'e>(Vec<&'e (dyn Error + 'static)>);
+
+impl<'e> CollectedChain<'e> {
+  /// Collect the chain starting at `error`.
+  pub fn new(error: &'e (dyn Error + 'static)) -> Self {
+    Self(Chain::new(error).collect())
+  }
+
+  /// Get the deepest error of the chain, within
+  /// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT).
+  pub fn root_cause(&self) -> Option<&'e (dyn Error + 'static)> {
+    self.0.last().copied()
+  }
+
+  /// Unwrap the collected errors.
+  pub fn into_vec(self) -> Vec<&'e (dyn Error + 'static)> {
+    self.0
+  }
+}
+
+impl<'e> Deref for CollectedChain<'e> {
+  type Target = [&'e (dyn Error + 'static)];
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl<'e> IntoIterator for CollectedChain<'e> {
+  type Item = &'e (dyn Error + 'static);
+  type IntoIter = ::alloc::vec::IntoIter<&'e (dyn Error + 'static)>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
+
+impl<'c, 'e> IntoIterator for &'c CollectedChain<'e> {
+  type Item = &'e (dyn Error + 'static);
+  type IntoIter = ::core::iter::Copied<::core::slice::Iter<'c, &'e (dyn Error + 'static)>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.iter().copied()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+  use ::alloc::vec::Vec;
+
+  #[test]
+  fn random_access_and_reverse() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let chain = input.collect_chain();
+    assert_eq!(chain.len(), 3);
+    assert_eq!(
+      chain.get(1).map(|e| e.to_string()),
+      Some(String::from("connection reset"))
+    );
+    let reversed: Vec<String> = (&chain).into_iter().rev().map(|e| e.to_string()).collect();
+    assert_eq!(reversed, ["permission denied", "connection reset", "upload failed"]);
+    let input = UploadError::Permission(PermissionError);
+    let chain = input.collect_chain();
+    assert!(chain.root_cause().is_some_and(|e| e.is::<PermissionError>()));
+    assert!(chain.into_iter().next_back().is_some_and(|e| e.is::<PermissionError>()));
+  }
+}
@@ -0,0 +1,247 @@
+//! Compatibility with unstable standard library APIs.
+//!
+//! The types of this module mirror unstable APIs of the standard library, so
+//! code written against them compiles on stable Rust. Once the standard APIs
+//! are stabilized, migrating is a matter of replacing the imports.
+
+use crate::{sources, DisplayFullError, Error, MESSAGE_LIMIT};
+use ::core::fmt::{self, Write};
+
+/// Error report, mirroring the unstable
+/// [`std::error::Report`](https://doc.rust-lang.org/nightly/std/error/struct.Report.html).
+///
+/// The default format prints the full chain on a single line, as
+/// [`DisplayFullError`](struct@DisplayFullError). The [`pretty`](Self::pretty)
+/// format prints each source on its own line, with the same layout as the
+/// standard library. The `Debug` format is the same as the `Display` format,
+/// so the report can be returned from `main`.
+///
+/// Unlike the standard type, there is no default for the `E` type parameter.
+///
+/// ```rust
+/// use ::display_full_error::compat::Report;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// assert_eq!(Report::new(err).pretty(true).to_string(), "permission denied");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Report<E> {
+  error: E,
+  show_backtrace: bool,
+  pretty: bool,
+}
+
+impl<E> Report<E>
+where
+  Report<E>: From<E>,
+{
+  /// Create a new report from an error.
+  pub fn new(error: E) -> Report<E> {
+    Self::from(error)
+  }
+}
+
+impl<E> Report<E> {
+  /// Enable the multiline format, printing each source on its own line.
+  #[must_use]
+  pub fn pretty(mut self, pretty: bool) -> Self {
+    self.pretty = pretty;
+    self
+  }
+
+  /// Display the backtrace provided by the error, if any.
+  ///
+  /// As with the standard type, the backtrace is only printed by the
+  /// [`pretty`](Self::pretty) format, after the sources. It is the first
+  /// [`Backtrace`](::std::backtrace::Backtrace) provided by the error or one of
+  /// its sources. Backtraces are retrieved with the generic member access API,
+  /// so they are only printed with the `std` feature and the
+  /// `display_full_error_unstable` cfg on nightly Rust; otherwise, this option
+  /// is accepted for compatibility but has no effect.
+  #[must_use]
+  pub fn show_backtrace(mut self, show_backtrace: bool) -> Self {
+    self.show_backtrace = show_backtrace;
+    self
+  }
+}
+
+impl<E> Report<E>
+where
+  E: Error,
+{
+  /// Write the multiline report.
+  fn fmt_multiline(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.error)?;
+    if let Some(cause) = self.error.source() {
+      f.write_str("\n\nCaused by:")?;
+      let multiple = cause.source().is_some();
+      let mut printed: u16 = 1;
+      for (index, e) in sources(&self.error).enumerate() {
+        f.write_char('\n')?;
+        if printed >= MESSAGE_LIMIT {
+          f.write_str("   ...")?;
+          break;
+        }
+        let mut indented = Indented { inner: &mut *f };
+        if multiple {
+          write!(indented, "{index: >4}: {e}")?;
+        } else {
+          write!(indented, "      {e}")?;
+        }
+        printed = printed.saturating_add(1);
+      }
+    }
+    #[cfg(all(feature = "std", display_full_error_unstable))]
+    if self.show_backtrace {
+      if let Some(backtrace) = self.backtrace() {
+        use ::std::string::ToString;
+
+        f.write_str("\n\nStack backtrace:\n")?;
+        f.write_str(backtrace.to_string().trim_end())?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Get the first backtrace provided by the error or its sources.
+  #[cfg(all(feature = "std", display_full_error_unstable))]
+  fn backtrace(&self) -> Option<&::std::backtrace::Backtrace> {
+    ::core::error::request_ref::<::std::backtrace::Backtrace>(&self.error).or_else(|| {
+      sources(&self.error)
+        .take(usize::from(MESSAGE_LIMIT))
+        .find_map(::core::error::request_ref::<::std::backtrace::Backtrace>)
+    })
+  }
+}
+
+impl<E> From<E> for Report<E>
+where
+  E: Error,
+{
+  fn from(error: E) -> Self {
+    Report {
+      error,
+      show_backtrace: false,
+      pretty: false,
+    }
+  }
+}
+
+impl<E> fmt::Display for Report<E>
+where
+  E: Error,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.pretty {
+      self.fmt_multiline(f)
+    } else {
+      fmt::Display::fmt(&DisplayFullError(&self.error), f)
+    }
+  }
+}
+
+impl<E> fmt::Debug for Report<E>
+where
+  Report<E>: fmt::Display,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+/// Writer indenting the continuation lines of a message.
+struct Indented<'f, W> {
+  inner: &'f mut W,
+}
+
+impl<W> Write for Indented<'_, W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for (i, line) in s.split('\n').enumerate() {
+      if i > 0 {
+        self.inner.write_str("\n      ")?;
+      }
+      self.inner.write_str(line)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Report;
+  use crate::tests::message_chain;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn single_line_report() {
+    let report = Report::new(message_chain(&["upload failed", "permission denied"]));
+    assert_eq!(report.to_string(), String::from("upload failed: permission denied"));
+  }
+
+  #[test]
+  fn pretty_report_single_source() {
+    let report = Report::new(message_chain(&["upload failed", "permission\ndenied"])).pretty(true);
+    let expected = String::from("upload failed\n\nCaused by:\n      permission\n      denied");
+    assert_eq!(report.to_string(), expected);
+  }
+
+  #[test]
+  fn pretty_report_multiple_sources() {
+    let report = Report::new(message_chain(&[
+      "upload failed",
+      "connection reset",
+      "permission denied",
+    ]))
+    .pretty(true);
+    let expected = String::from("upload failed\n\nCaused by:\n   0: connection reset\n   1: permission denied");
+    assert_eq!(report.to_string(), expected);
+  }
+
+  #[cfg(all(feature = "std", display_full_error_unstable))]
+  #[test]
+  fn pretty_report_backtrace() {
+    use crate::tests::{message_chain, MessageError};
+    use ::core::{error, fmt};
+    use ::std::backtrace::Backtrace;
+
+    #[derive(Debug)]
+    struct TracedError {
+      source: MessageError,
+      backtrace: Backtrace,
+    }
+
+    impl fmt::Display for TracedError {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("connection reset")
+      }
+    }
+
+    impl error::Error for TracedError {
+      fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+      }
+
+      fn provide<'a>(&'a self, request: &mut error::Request<'a>) {
+        request.provide_ref::<Backtrace>(&self.backtrace);
+      }
+    }
+
+    let error = || TracedError {
+      source: message_chain(&["permission denied"]),
+      backtrace: Backtrace::disabled(),
+    };
+    let report = Report::new(error()).pretty(true);
+    let expected = String::from("connection reset\n\nCaused by:\n      permission denied");
+    assert_eq!(report.to_string(), expected);
+    let report = report.show_backtrace(true);
+    assert_eq!(
+      report.to_string(),
+      expected.clone() + "\n\nStack backtrace:\ndisabled backtrace"
+    );
+    let report = Report::new(error()).show_backtrace(true);
+    assert_eq!(report.to_string(), String::from("connection reset: permission denied"));
+  }
+}
@@ -0,0 +1,218 @@
+//! Context errors, wrapping a source error with a message.
+
+use crate::Error;
+#[cfg(feature = "alloc")]
+use ::alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use ::alloc::string::{String, ToString};
+use ::core::fmt;
+use ::core::panic::Location;
+
+/// Error adding a context message to a source error, with the location of
+/// the call site.
+///
+/// The `Display` implementation prints the context message. With the
+/// alternate flag (`{:#}`), it is followed by the location, such as
+/// `failed to run job at src/job.rs:87`. The alternate flag is forwarded to
+/// the messages by [`DisplayFullError`](struct@crate::DisplayFullError), so
+/// the locations of the whole chain are printed with
+/// `format!("{:#}", error.display_full())`. This does not require a backtrace.
+///
+/// Create it with the [`context_here!`](crate::context_here) macro or
+/// [`ContextError::new`]. With the `display_full_error_unstable` cfg flag,
+/// [`Error::provide`](core::error::Error::provide) is forwarded to the source,
+/// so its provided members (such as backtraces) survive the wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContextError<C, E> {
+  context: C,
+  location: &'static Location<'static>,
+  source: E,
+}
+
+impl<C, E> ContextError<C, E> {
+  /// Wrap `source` with the `context` message, recording the location of the
+  /// caller.
+  #[track_caller]
+  pub fn new(context: C, source: E) -> Self {
+    Self {
+      context,
+      location: Location::caller(),
+      source,
+    }
+  }
+
+  /// Get the context message.
+  pub fn context(&self) -> &C {
+    &self.context
+  }
+
+  /// Get the location where this error was created.
+  pub fn location(&self) -> &'static Location<'static> {
+    self.location
+  }
+
+  /// Unwrap the source error.
+  pub fn into_source(self) -> E {
+    self.source
+  }
+}
+
+impl<C, E> fmt::Display for ContextError<C, E>
+where
+  C: fmt::Display,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(&self.context, f)?;
+    if f.alternate() {
+      write!(f, " at {}:{}", self.location.file(), self.location.line())?;
+    }
+    Ok(())
+  }
+}
+
+impl<C, E> Error for ContextError<C, E>
+where
+  C: fmt::Display + fmt::Debug,
+  E: Error + 'static,
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    Some(&self.source)
+  }
+
+  #[cfg(display_full_error_unstable)]
+  fn provide<'a>(&'a self, request: &mut ::core::error::Request<'a>) {
+    self.source.provide(request);
+  }
+}
+
+/// Wrap an error with a context message and the location of the call site.
+///
+/// `context_here!(error, context)` returns a [`ContextError`]. The context can
+/// be any `Display` value.
+///
+/// ```rust
+/// use ::display_full_error::{context_here, DisplayFullErrorExt};
+///
+/// let err = context_here!(::std::io::Error::other("permission denied"), "failed to run job");
+/// assert_eq!(err.display_full().to_string(), "failed to run job: permission denied");
+/// let line = line!() - 2;
+/// assert_eq!(
+///   format!("{:#}", err.display_full()),
+///   format!("failed to run job at {}:{line}: permission denied", file!())
+/// );
+/// ```
+#[macro_export]
+macro_rules! context_here {
+  ($error:expr, $context:expr $(,)?) => {
+    $crate::ContextError::new($context, $error)
+  };
+}
+
+/// Error adding a context message to a boxed source error.
+///
+/// This is the error type returned by
+/// [`context_full`](crate::DisplayFullResultExt::context_full). It only holds
+/// the message and the source: the `Display` implementation prints the
+/// message, and the source is returned by
+/// [`source`](::core::error::Error::source), so the chain is formatted
+/// exactly as any other error by this crate. As for [`ContextError`],
+/// [`Error::provide`](core::error::Error::provide) is forwarded to the source
+/// with the `display_full_error_unstable` cfg flag.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct ChainedError {
+  message: String,
+  source: Box<dyn Error + Send + Sync + 'static>,
+}
+
+#[cfg(feature = "alloc")]
+impl ChainedError {
+  /// Wrap `source` with the `message`.
+  pub fn new<M, E>(message: M, source: E) -> Self
+  where
+    M: fmt::Display,
+    E: Error + Send + Sync + 'static,
+  {
+    Self {
+      message: message.to_string(),
+      source: Box::new(source),
+    }
+  }
+
+  /// Get the context message.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+  /// Unwrap the source error.
+  pub fn into_source(self) -> Box<dyn Error + Send + Sync + 'static> {
+    self.source
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for ChainedError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl Error for ChainedError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    Some(&*self.source)
+  }
+
+  #[cfg(display_full_error_unstable)]
+  fn provide<'a>(&'a self, request: &mut ::core::error::Request<'a>) {
+    self.source.provide(request);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ContextError;
+  use crate::tests::PermissionError;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::format;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn context_location() {
+    let input = context_here!(context_here!(PermissionError, "upload failed"), "job failed");
+    let line = line!() - 1;
+    assert_eq!(input.location().line(), line);
+    assert_eq!(
+      input.display_full().to_string(),
+      String::from("job failed: upload failed: permission denied")
+    );
+    let expected = format!(
+      "job failed at {0}:{line}: upload failed at {0}:{line}: permission denied",
+      file!()
+    );
+    assert_eq!(format!("{:#}", input.display_full()), expected);
+    let input: ContextError<&str, PermissionError> = ContextError::new("upload failed", PermissionError);
+    assert_eq!(input.into_source(), PermissionError);
+  }
+
+  #[test]
+  #[cfg(display_full_error_unstable)]
+  fn context_forwards_provide() {
+    use crate::tests::QueryError;
+
+    let input = context_here!(QueryError, "job failed");
+    assert_eq!(::core::error::request_ref::<str>(&input), Some("SELECT 1"));
+  }
+
+  #[test]
+  #[cfg(all(feature = "alloc", display_full_error_unstable))]
+  fn chained_forwards_provide() {
+    use super::ChainedError;
+    use crate::tests::QueryError;
+
+    let input = ChainedError::new("job failed", QueryError);
+    assert_eq!(::core::error::request_ref::<str>(&input), Some("SELECT 1"));
+  }
+}
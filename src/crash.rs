@@ -0,0 +1,154 @@
+//! Crash report files, for errors ending the program.
+
+use crate::{fingerprint::fingerprint, Clock, DisplayFullError, DisplayRfc3339, Error};
+use ::core::fmt;
+use ::std::backtrace::{Backtrace, BacktraceStatus};
+use ::std::fs::OpenOptions;
+use ::std::io::{self, Write};
+use ::std::path::{Path, PathBuf};
+use ::std::time::{SystemTime, UNIX_EPOCH};
+
+/// Crash report for an error ending the program.
+///
+/// The report contains the full error chain, its
+/// [fingerprint](crate::DisplayFullErrorExt::fingerprint), basic information
+/// about the environment, and the backtrace if one is provided. This is in the
+/// spirit of `human-panic`, but for returned errors rather than panics.
+///
+/// The `Display` implementation prints the text of the report, use
+/// [`write`](Self::write) to save it in a file.
+///
+/// Requires the `std` feature.
+///
+/// ```rust,no_run
+/// use ::display_full_error::CrashReport;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// let path = CrashReport::new(&err).write().expect("failed to write crash report");
+/// eprintln!("crash report written to {}", path.display());
+/// ```
+#[derive(Debug)]
+pub struct CrashReport<'e, E>
+where
+  E: Error + ?Sized,
+{
+  error: &'e E,
+  backtrace: Option<&'e Backtrace>,
+  timestamp: SystemTime,
+}
+
+impl<'e, E> CrashReport<'e, E>
+where
+  E: Error + ?Sized,
+{
+  /// Create a new crash report for `error`, at the current time.
+  pub fn new(error: &'e E) -> Self {
+    Self {
+      error,
+      backtrace: None,
+      timestamp: SystemTime::now(),
+    }
+  }
+
+  /// Include a backtrace in the report.
+  ///
+  /// The backtrace is only printed if it was captured.
+  #[must_use]
+  pub fn backtrace(mut self, backtrace: &'e Backtrace) -> Self {
+    self.backtrace = Some(backtrace);
+    self
+  }
+
+  /// Read the time of the report from `clock`, instead of the system time.
+  #[must_use]
+  pub fn clock(mut self, clock: Clock) -> Self {
+    self.timestamp = UNIX_EPOCH.checked_add(clock()).unwrap_or(UNIX_EPOCH);
+    self
+  }
+
+  /// Write the report in a new file in the temporary directory.
+  ///
+  /// Returns the path of the file. See [`write_to_dir`](Self::write_to_dir).
+  pub fn write(&self) -> io::Result<PathBuf> {
+    self.write_to_dir(&::std::env::temp_dir())
+  }
+
+  /// Write the report in a new file in `dir`.
+  ///
+  /// The file is named `crash-report-{timestamp}-{fingerprint}.txt`. Existing
+  /// files are never overwritten. Returns the path of the file.
+  pub fn write_to_dir(&self, dir: &Path) -> io::Result<PathBuf> {
+    let name = ::std::format!(
+      "crash-report-{}-{:016x}.txt",
+      self.unix_timestamp(),
+      fingerprint(self.error)
+    );
+    let path = dir.join(name);
+    let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+    write!(file, "{self}")?;
+    file.flush()?;
+    Ok(path)
+  }
+
+  /// Get the timestamp of the report, in seconds since the Unix epoch.
+  fn unix_timestamp(&self) -> u64 {
+    self
+      .timestamp
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or_default()
+  }
+}
+
+impl<E> fmt::Display for CrashReport<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "error: {}", DisplayFullError(self.error))?;
+    writeln!(f, "fingerprint: {:016x}", fingerprint(self.error))?;
+    writeln!(f, "timestamp: {}", self.unix_timestamp())?;
+    let since_epoch = self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    writeln!(f, "date: {}", DisplayRfc3339(since_epoch))?;
+    writeln!(f, "os: {}", ::std::env::consts::OS)?;
+    writeln!(f, "arch: {}", ::std::env::consts::ARCH)?;
+    match ::std::env::current_exe() {
+      Ok(exe) => writeln!(f, "executable: {}", exe.display())?,
+      Err(_) => writeln!(f, "executable: unknown")?,
+    }
+    if let Some(backtrace) = self.backtrace.filter(|b| b.status() == BacktraceStatus::Captured) {
+      write!(f, "\nbacktrace:\n{backtrace}")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::CrashReport;
+  use crate::tests::message_chain;
+  use ::alloc::string::ToString;
+  use ::core::time::Duration;
+
+  #[test]
+  fn report_content() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let report = CrashReport::new(&input)
+      .clock(|| Duration::from_secs(1_709_210_096))
+      .to_string();
+    assert!(report.starts_with("error: upload failed: permission denied\nfingerprint: "));
+    assert!(report.contains("\ntimestamp: 1709210096\ndate: 2024-02-29T12:34:56.000Z\n"));
+    assert!(report.contains(&::std::format!("\nos: {}\n", ::std::env::consts::OS)));
+  }
+
+  #[test]
+  fn write_report_file() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let dir = ::std::env::temp_dir();
+    let path = CrashReport::new(&input).write_to_dir(&dir);
+    let path = path.expect("crash report should be written");
+    let content = ::std::fs::read_to_string(&path).expect("crash report should be readable");
+    let _ = ::std::fs::remove_file(&path);
+    assert!(content.starts_with("error: upload failed: permission denied\n"));
+  }
+}
@@ -0,0 +1,67 @@
+//! CSV output mode, quoting the chain as a single field.
+
+use crate::{DisplayFullError, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the full error chain as a CSV field.
+///
+/// The output is the same as [`DisplayFullError`], quoted and escaped as
+/// specified by [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180): the field
+/// is always enclosed in double quotes and double quotes inside messages are
+/// doubled. Commas and line breaks are preserved inside the quoted field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorCsv<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorCsv<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('"')?;
+    write!(CsvEscape(&mut *f), "{}", DisplayFullError(self.0))?;
+    f.write_char('"')
+  }
+}
+
+/// Writer doubling the double quotes written to the inner writer.
+struct CsvEscape<W>(W);
+
+impl<W> Write for CsvEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for (i, part) in s.split('"').enumerate() {
+      if i > 0 {
+        self.0.write_str("\"\"")?;
+      }
+      self.0.write_str(part)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn csv_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_csv().to_string();
+    let expected = String::from("\"upload failed: permission denied\"");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn csv_escapes_quotes() {
+    let input = message_chain(&["upload of \"a, b\" failed", "line 1\nline 2"]);
+    let actual: String = input.display_csv().to_string();
+    let expected = String::from("\"upload of \"\"a, b\"\" failed: line 1\nline 2\"");
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,53 @@
+//! Debug formatting of messages, as quoted strings.
+
+use ::core::fmt::{self, Write};
+
+/// Adapter printing the `Display` output of a message as a `Debug` string
+/// literal, without allocating.
+pub(crate) struct DebugMessage<M>(pub(crate) M);
+
+impl<M> fmt::Debug for DebugMessage<M>
+where
+  M: fmt::Display,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('"')?;
+    write!(DebugEscape(&mut *f), "{}", self.0)?;
+    f.write_char('"')
+  }
+}
+
+/// Writer escaping the text written to the inner writer as the content of a
+/// `Debug` string literal.
+struct DebugEscape<W>(W);
+
+impl<W> Write for DebugEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() {
+      // Single quotes are only escaped in char literals
+      if c == '\'' {
+        self.0.write_char(c)?;
+      } else {
+        for escaped in c.escape_debug() {
+          self.0.write_char(escaped)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DebugMessage;
+  use ::alloc::format;
+
+  #[test]
+  fn matches_str_debug() {
+    let message = "it's a \"quote\"\n\tand a \\ backslash";
+    assert_eq!(format!("{:?}", DebugMessage(message)), format!("{message:?}"));
+  }
+}
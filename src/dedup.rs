@@ -0,0 +1,131 @@
+//! Reporter suppressing the chains reported recently.
+
+use crate::fingerprint::fingerprint;
+use crate::{Error, ErrorReporter};
+use ::std::collections::VecDeque;
+use ::std::sync::{Mutex, PoisonError};
+use ::std::time::{Duration, Instant};
+
+/// Reporter forwarding each chain to an inner reporter at most once per time
+/// window.
+///
+/// Chains are identified by their
+/// [fingerprint](crate::DisplayFullErrorExt::fingerprint). A chain reported
+/// less than `window` ago is suppressed, so a failing retry loop doesn't emit
+/// thousands of identical lines. At most `capacity` fingerprints are tracked,
+/// the oldest ones are forgotten first.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::std::time::Duration;
+/// use ::display_full_error::{DedupReporter, ErrorReporter, StderrReporter};
+///
+/// let reporter = DedupReporter::new(StderrReporter, Duration::from_secs(60), 256);
+/// for _ in 0..1000 {
+///   // Printed once
+///   reporter.report(&::std::io::Error::other("connection refused"));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DedupReporter<R> {
+  inner: R,
+  window: Duration,
+  capacity: usize,
+  /// Fingerprints with the time when they were last forwarded, oldest first.
+  seen: Mutex<VecDeque<(u64, Instant)>>,
+}
+
+impl<R> DedupReporter<R>
+where
+  R: ErrorReporter,
+{
+  /// Create a new reporter forwarding to `inner`, suppressing the chains
+  /// already reported in the last `window`, and tracking up to `capacity`
+  /// fingerprints.
+  pub fn new(inner: R, window: Duration, capacity: usize) -> Self {
+    Self {
+      inner,
+      window,
+      capacity,
+      seen: Mutex::new(VecDeque::new()),
+    }
+  }
+
+  /// Get a reference to the inner reporter.
+  pub fn inner(&self) -> &R {
+    &self.inner
+  }
+
+  /// Check if the chain with `fingerprint` should be forwarded at `now`, and
+  /// record it if so.
+  fn should_report(&self, fingerprint: u64, now: Instant) -> bool {
+    let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+    while let Some(&(_, time)) = seen.front() {
+      if now.saturating_duration_since(time) < self.window {
+        break;
+      }
+      seen.pop_front();
+    }
+    if seen.iter().any(|&(seen, _)| seen == fingerprint) {
+      return false;
+    }
+    if self.capacity == 0 {
+      return true;
+    }
+    if seen.len() >= self.capacity {
+      seen.pop_front();
+    }
+    seen.push_back((fingerprint, now));
+    true
+  }
+
+  /// Report `error` as if the current time was `now`.
+  fn report_at(&self, error: &(dyn Error + 'static), now: Instant) {
+    if self.should_report(fingerprint(error), now) {
+      self.inner.report(error);
+    }
+  }
+}
+
+impl<R> ErrorReporter for DedupReporter<R>
+where
+  R: ErrorReporter,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.report_at(error, Instant::now());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DedupReporter;
+  use crate::tests::message_chain;
+  use crate::WriteReporter;
+  use ::std::time::{Duration, Instant};
+
+  #[test]
+  fn suppress_within_window() {
+    let reporter = DedupReporter::new(WriteReporter::new(::std::vec::Vec::new()), Duration::from_secs(60), 16);
+    let start = Instant::now();
+    let error = message_chain(&["upload failed", "permission denied"]);
+    reporter.report_at(&error, start);
+    reporter.report_at(&error, start + Duration::from_secs(30));
+    reporter.report_at(&message_chain(&["job failed"]), start + Duration::from_secs(30));
+    reporter.report_at(&error, start + Duration::from_secs(61));
+    let DedupReporter { inner, .. } = reporter;
+    let expected = b"upload failed: permission denied\njob failed\nupload failed: permission denied\n";
+    assert_eq!(inner.into_inner(), expected);
+  }
+
+  #[test]
+  fn evict_oldest_when_full() {
+    let reporter = DedupReporter::new(WriteReporter::new(::std::vec::Vec::new()), Duration::from_secs(60), 1);
+    let now = Instant::now();
+    reporter.report_at(&message_chain(&["first"]), now);
+    reporter.report_at(&message_chain(&["second"]), now);
+    reporter.report_at(&message_chain(&["first"]), now);
+    let DedupReporter { inner, .. } = reporter;
+    assert_eq!(inner.into_inner(), b"first\nsecond\nfirst\n");
+  }
+}
@@ -0,0 +1,105 @@
+//! Compilation tests for the checks of the
+//! [`DisplayFullError`](macro@crate::DisplayFullError) derive macro.
+//!
+//! The source is rejected when referenced inline:
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload failed: {source:?}")]
+//! struct UploadError {
+//!   source: PermissionError,
+//! }
+//! ```
+//!
+//! As a positional argument:
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload failed: {}", source)]
+//! struct UploadError {
+//!   source: PermissionError,
+//! }
+//! ```
+//!
+//! As a named argument:
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload failed: {x}", x = &source)]
+//! struct UploadError {
+//!   source: PermissionError,
+//! }
+//! ```
+//!
+//! After escaped braces:
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload failed: {{{source}}}")]
+//! struct UploadError {
+//!   source: PermissionError,
+//! }
+//! ```
+//!
+//! As a tuple field:
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! #[derive(Debug, DisplayFullError)]
+//! enum UploadError {
+//!   #[display("upload failed: {}", _0)]
+//!   Permission(#[source] PermissionError),
+//! }
+//! ```
+//!
+//! Escaped braces and other fields are accepted:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use ::display_full_error::{DisplayFullError, DisplayFullErrorExt};
+//!
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("permission denied")]
+//! struct PermissionError;
+//!
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload of {{source}} failed after {}s", seconds)]
+//! struct UploadError {
+//!   seconds: u64,
+//!   source: PermissionError,
+//! }
+//!
+//! let err = UploadError { seconds: 3, source: PermissionError };
+//! assert_eq!(err.display_full().to_string(), "upload of {source} failed after 3s: permission denied");
+//! # }
+//! ```
@@ -0,0 +1,105 @@
+//! Comparison of two error chains, for readable test failures.
+
+use crate::{Error, IndexedMessages};
+use ::alloc::string::{String, ToString};
+use ::core::fmt;
+
+/// First difference between two error chains.
+///
+/// It is displayed with the diverging depth and the two differing messages,
+/// which is much more readable than comparing long formatted chains.
+///
+/// Requires the `alloc` feature.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let left = ::std::io::Error::other("permission denied");
+/// let right = ::std::io::Error::other("disk full");
+/// let diff = left.diff_chain(&right).expect("chains are different");
+/// assert_eq!(diff.depth, 0);
+/// assert_eq!(diff.to_string(), "chains differ at depth 0\n  left:  permission denied\n  right: disk full");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChainDiff {
+  /// Depth of the first differing message, `0` is the outermost error.
+  pub depth: usize,
+  /// Message of the left chain at this depth, `None` if the chain is shorter.
+  pub left: Option<String>,
+  /// Message of the right chain at this depth, `None` if the chain is shorter.
+  pub right: Option<String>,
+}
+
+impl fmt::Display for ChainDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "chains differ at depth {}", self.depth)?;
+    for (label, message) in [("left: ", &self.left), ("right:", &self.right)] {
+      match message {
+        Some(message) => write!(f, "\n  {label} {message}")?,
+        None => write!(f, "\n  {label} <end of chain>")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Find the first difference between the chains of `left` and `right`.
+pub(crate) fn diff_chains<L, R>(left: &L, right: &R) -> Option<ChainDiff>
+where
+  L: Error + ?Sized,
+  R: Error + ?Sized,
+{
+  let mut left = IndexedMessages::new(left);
+  let mut right = IndexedMessages::new(right);
+  loop {
+    let (depth, left, right) = match (left.next(), right.next()) {
+      (None, None) => return None,
+      (Some((depth, l)), Some((_, r))) => {
+        let (l, r) = (l.to_string(), r.to_string());
+        if l == r {
+          continue;
+        }
+        (depth, Some(l), Some(r))
+      }
+      (Some((depth, l)), None) => (depth, Some(l.to_string()), None),
+      (None, Some((depth, r))) => (depth, None, Some(r.to_string())),
+    };
+    return Some(ChainDiff { depth, left, right });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ChainDiff;
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn equal_chains() {
+    let left = message_chain(&["upload failed", "permission denied"]);
+    let right = message_chain(&["upload failed", "permission denied"]);
+    assert_eq!(left.diff_chain(&right), None);
+  }
+
+  #[test]
+  fn different_message() {
+    let left = message_chain(&["upload failed", "permission denied"]);
+    let right = message_chain(&["upload failed", "disk full"]);
+    let expected = ChainDiff {
+      depth: 1,
+      left: Some(String::from("permission denied")),
+      right: Some(String::from("disk full")),
+    };
+    assert_eq!(left.diff_chain(&right), Some(expected));
+  }
+
+  #[test]
+  fn shorter_chain() {
+    let left = message_chain(&["upload failed", "permission denied"]);
+    let right = message_chain(&["upload failed"]);
+    let diff = left.diff_chain(&right).map(|diff| diff.to_string());
+    let expected = String::from("chains differ at depth 1\n  left:  permission denied\n  right: <end of chain>");
+    assert_eq!(diff, Some(expected));
+  }
+}
@@ -0,0 +1,94 @@
+//! Graphviz DOT output mode, to visualize the chain as a graph.
+
+use crate::{printed_sources, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the error chain as a Graphviz DOT graph.
+///
+/// Each message of the chain is a node (`n0` is the outermost error), with an
+/// edge from each error to its source. Labels are escaped as DOT quoted
+/// strings. The chain is printed up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT)
+/// messages, if it is longer the last node is labeled `...`.
+///
+/// Since [`Error::source`](core::error::Error::source) returns a single
+/// source, the graph is always a path.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// assert_eq!(err.display_dot().to_string(), "digraph error {\n  n0 [label=\"permission denied\"];\n}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorDot<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorDot<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("digraph error {\n  n0 [label=\"")?;
+    write!(DotEscape(&mut *f), "{}", self.0)?;
+    f.write_str("\"];")?;
+    for (parent, source) in printed_sources(self.0).enumerate() {
+      let node = parent.saturating_add(1);
+      write!(f, "\n  n{node} [label=\"")?;
+      write!(DotEscape(&mut *f), "{source}")?;
+      write!(f, "\"];\n  n{parent} -> n{node};")?;
+    }
+    f.write_str("\n}")
+  }
+}
+
+/// Writer escaping the text written to the inner writer as the content of a
+/// DOT quoted string.
+struct DotEscape<W>(W);
+
+impl<W> Write for DotEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let mut rest = s;
+    while let Some(index) = rest.find(['"', '\\', '\n', '\r']) {
+      let (text, special) = rest.split_at(index);
+      self.0.write_str(text)?;
+      let mut chars = special.chars();
+      match chars.next() {
+        Some('"') => self.0.write_str("\\\"")?,
+        Some('\\') => self.0.write_str("\\\\")?,
+        Some('\n') => self.0.write_str("\\n")?,
+        Some(_) | None => {}
+      }
+      rest = chars.as_str();
+    }
+    self.0.write_str(rest)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn dot_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_dot().to_string();
+    let expected = String::from(
+      "digraph error {\n  n0 [label=\"upload failed\"];\n  n1 [label=\"permission denied\"];\n  n0 -> n1;\n}",
+    );
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn dot_escapes_labels() {
+    let input = message_chain(&["path \"C:\\tmp\"\r\nline 2"]);
+    let actual: String = input.display_dot().to_string();
+    let expected = String::from("digraph error {\n  n0 [label=\"path \\\"C:\\\\tmp\\\"\\nline 2\"];\n}");
+    assert_eq!(actual, expected);
+  }
+}
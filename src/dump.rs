@@ -0,0 +1,265 @@
+//! Compact binary encoding of the chain, to persist it across reboots.
+//!
+//! The format is:
+//!
+//! - the format version, as a single byte (currently `1`);
+//! - the number of messages, as an unsigned LEB128 varint;
+//! - each message, as its byte length (unsigned LEB128 varint) followed by its
+//!   UTF-8 bytes, starting with the outermost error;
+//! - a checksum, as 4 little-endian bytes: the lower 32 bits of the 64-bit
+//!   FNV-1a hash of all the previous bytes.
+//!
+//! The chain is encoded up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT)
+//! messages, if it is longer the last message is `...`.
+
+use crate::fingerprint::Fnv1a;
+use crate::postcard::{encode_postcard_into, SliceWriter};
+use crate::{EncodeError, Error};
+use ::core::fmt;
+use ::core::iter::FusedIterator;
+
+/// Version of the binary format.
+const VERSION: u8 = 1;
+
+/// Size of the checksum, in bytes.
+const CHECKSUM_LEN: usize = 4;
+
+/// Encode the chain of `error` into `buf`, returning the number of bytes
+/// written.
+pub(crate) fn encode_into<E>(error: &E, buf: &mut [u8]) -> Result<usize, EncodeError>
+where
+  E: Error + ?Sized,
+{
+  let (version, rest) = buf.split_first_mut().ok_or(EncodeError::BufferTooSmall)?;
+  *version = VERSION;
+  let len = encode_postcard_into(error, rest)?.saturating_add(1);
+  if len > buf.len() {
+    return Err(EncodeError::BufferTooSmall);
+  }
+  let (content, rest) = buf.split_at_mut(len);
+  let checksum = checksum(content);
+  let mut writer = SliceWriter { buf: rest, written: 0 };
+  writer.write_bytes(&checksum)?;
+  Ok(len.saturating_add(CHECKSUM_LEN))
+}
+
+/// Compute the checksum of the encoded content.
+fn checksum(content: &[u8]) -> [u8; CHECKSUM_LEN] {
+  let mut hasher = Fnv1a::new();
+  hasher.write_bytes(content);
+  let [a, b, c, d, ..] = hasher.finish().to_le_bytes();
+  [a, b, c, d]
+}
+
+/// Error chain decoded from the compact binary format.
+///
+/// Dumps are created with
+/// [`encode_into`](crate::DisplayFullErrorExt::encode_into), and are small
+/// enough to store the last error in an EEPROM or a backup register and decode
+/// it after a reboot. Decoding doesn't copy the messages, and doesn't
+/// allocate. The format is documented in the source of this module.
+///
+/// The `Display` format is the same as
+/// [`DisplayFullError`](struct@crate::DisplayFullError) for the original
+/// error.
+///
+/// ```rust
+/// use ::display_full_error::{CrashDump, DisplayFullErrorExt};
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// let mut buf = [0u8; 64];
+/// let len = err.encode_into(&mut buf).expect("buffer is large enough");
+/// let dump = CrashDump::decode(&buf[..len]).expect("dump is valid");
+/// assert_eq!(dump.to_string(), "permission denied");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CrashDump<'b> {
+  /// Number of messages.
+  count: usize,
+  /// Encoded messages, validated.
+  messages: &'b [u8],
+}
+
+impl<'b> CrashDump<'b> {
+  /// Decode a chain from `buf`.
+  ///
+  /// Trailing bytes after the encoded chain are ignored, so the whole storage
+  /// area can be passed.
+  pub fn decode(buf: &'b [u8]) -> Result<Self, DecodeError> {
+    let (&version, rest) = buf.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+    if version != VERSION {
+      return Err(DecodeError::UnsupportedVersion(version));
+    }
+    let mut reader = Reader(rest);
+    let count = reader.read_varint()?;
+    let messages = reader.0;
+    for _ in 0..count {
+      reader.read_message()?;
+    }
+    let messages_len = messages.len().saturating_sub(reader.0.len());
+    let content_len = buf.len().saturating_sub(reader.0.len());
+    let stored = reader.0.get(..CHECKSUM_LEN).ok_or(DecodeError::UnexpectedEnd)?;
+    let content = buf.get(..content_len).ok_or(DecodeError::UnexpectedEnd)?;
+    if stored != checksum(content) {
+      return Err(DecodeError::InvalidChecksum);
+    }
+    Ok(Self {
+      count,
+      messages: messages.get(..messages_len).ok_or(DecodeError::UnexpectedEnd)?,
+    })
+  }
+
+  /// Get the number of messages.
+  pub fn len(&self) -> usize {
+    self.count
+  }
+
+  /// Check if the chain has no messages.
+  pub fn is_empty(&self) -> bool {
+    self.count == 0
+  }
+
+  /// Iterate over the messages, starting with the outermost error.
+  pub fn messages(&self) -> DumpMessages<'b> {
+    DumpMessages {
+      reader: Reader(self.messages),
+      remaining: self.count,
+    }
+  }
+}
+
+impl fmt::Display for CrashDump<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, message) in self.messages().enumerate() {
+      if i > 0 {
+        f.write_str(": ")?;
+      }
+      f.write_str(message)?;
+    }
+    Ok(())
+  }
+}
+
+/// Iterator over the messages of a [`CrashDump`].
+#[derive(Debug, Clone)]
+pub struct DumpMessages<'b> {
+  reader: Reader<'b>,
+  remaining: usize,
+}
+
+impl<'b> Iterator for DumpMessages<'b> {
+  type Item = &'b str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.remaining = self.remaining.checked_sub(1)?;
+    // The messages were validated when decoding the dump
+    self.reader.read_message().ok()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl ExactSizeIterator for DumpMessages<'_> {}
+
+impl FusedIterator for DumpMessages<'_> {}
+
+/// Error returned when decoding a [`CrashDump`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecodeError {
+  /// The buffer ends before the end of the encoded chain.
+  UnexpectedEnd,
+  /// The format version is not supported.
+  UnsupportedVersion(u8),
+  /// A length doesn't fit in `usize`.
+  InvalidLength,
+  /// A message is not valid UTF-8.
+  InvalidUtf8,
+  /// The checksum doesn't match the content, which is corrupted.
+  InvalidChecksum,
+}
+
+impl fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DecodeError::UnexpectedEnd => f.write_str("unexpected end of the crash dump"),
+      DecodeError::UnsupportedVersion(version) => write!(f, "unsupported crash dump version {version}"),
+      DecodeError::InvalidLength => f.write_str("invalid length in the crash dump"),
+      DecodeError::InvalidUtf8 => f.write_str("invalid UTF-8 message in the crash dump"),
+      DecodeError::InvalidChecksum => f.write_str("invalid crash dump checksum"),
+    }
+  }
+}
+
+impl Error for DecodeError {}
+
+/// Reader consuming the encoded bytes.
+#[derive(Debug, Clone)]
+struct Reader<'b>(&'b [u8]);
+
+impl<'b> Reader<'b> {
+  /// Read an unsigned LEB128 varint.
+  fn read_varint(&mut self) -> Result<usize, DecodeError> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+      let (&byte, rest) = self.0.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+      self.0 = rest;
+      let bits = u64::from(byte & 0x7f);
+      if bits.checked_shl(shift).and_then(|b| b.checked_shr(shift)) != Some(bits) {
+        return Err(DecodeError::InvalidLength);
+      }
+      value |= bits << shift;
+      if byte & 0x80 == 0 {
+        return usize::try_from(value).map_err(|_| DecodeError::InvalidLength);
+      }
+    }
+    Err(DecodeError::InvalidLength)
+  }
+
+  /// Read a message prefixed with its length.
+  fn read_message(&mut self) -> Result<&'b str, DecodeError> {
+    let len = self.read_varint()?;
+    if len > self.0.len() {
+      return Err(DecodeError::UnexpectedEnd);
+    }
+    let (message, rest) = self.0.split_at(len);
+    self.0 = rest;
+    ::core::str::from_utf8(message).map_err(|_| DecodeError::InvalidUtf8)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{CrashDump, DecodeError};
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+  use ::alloc::vec::Vec;
+
+  #[test]
+  fn round_trip() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let mut buf = [0xffu8; 64];
+    let len = input.encode_into(&mut buf);
+    assert_eq!(len, Ok(38));
+    let dump = CrashDump::decode(&buf);
+    let messages: Option<Vec<&str>> = dump.ok().map(|dump| dump.messages().collect());
+    assert_eq!(messages, Some(::alloc::vec!["upload failed", "permission denied"]));
+    let actual = CrashDump::decode(&buf).map(|dump| dump.to_string());
+    assert_eq!(actual, Ok(String::from("upload failed: permission denied")));
+  }
+
+  #[test]
+  fn detect_corruption() {
+    let input = message_chain(&["upload failed"]);
+    let mut buf = [0u8; 32];
+    let _ = input.encode_into(&mut buf);
+    if let Some(byte) = buf.get_mut(3) {
+      *byte ^= 1;
+    }
+    assert_eq!(CrashDump::decode(&buf), Err(DecodeError::InvalidChecksum));
+    assert_eq!(CrashDump::decode(&[]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(CrashDump::decode(&[0xff]), Err(DecodeError::UnsupportedVersion(0xff)));
+  }
+}
@@ -0,0 +1,99 @@
+//! Elided display mode, printing the outermost and deepest messages of the
+//! chain.
+
+use crate::{chain_len, sources, Error};
+use ::core::fmt;
+
+/// Formatting wrapper to display the outermost and deepest messages of an
+/// error chain, eliding the middle.
+///
+/// The second field is the number of messages to keep at each end of the
+/// chain. Messages are formatted on a single line, separated with `: `. If
+/// the chain has more than twice this number of messages, the intermediate
+/// messages are replaced with `... (N omitted) ...`. The chain is considered
+/// up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages: if it is longer,
+/// the last messages are the deepest ones before the limit (see
+/// [`deepest_source`](crate::DisplayFullErrorExt::deepest_source)), and the
+/// count only includes the omitted messages before the limit.
+///
+/// This keeps both the outer context and the root cause, which are usually
+/// more valuable than the intermediate layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorElided<'e, E>(pub &'e E, pub usize)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorElided<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (len, _) = chain_len(self.0);
+    let keep = self.1;
+    let omitted = len.saturating_sub(keep.saturating_mul(2));
+    // Depths in `first_omitted..resume` are replaced with the elision marker.
+    let first_omitted = if omitted == 0 { len } else { keep };
+    let resume = first_omitted.saturating_add(omitted);
+    if first_omitted == 0 {
+      write!(f, "... ({omitted} omitted) ...")?;
+    } else {
+      fmt::Display::fmt(&self.0, f)?;
+    }
+    let mut depth: usize = 1;
+    for e in sources(self.0).take(len.saturating_sub(1)) {
+      if depth == first_omitted {
+        write!(f, ": ... ({omitted} omitted) ...")?;
+      }
+      if depth >= resume || depth < first_omitted {
+        f.write_str(": ")?;
+        fmt::Display::fmt(e, f)?;
+      }
+      depth = depth.saturating_add(1);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, CyclicError};
+  use crate::{DisplayFullErrorExt, MESSAGE_LIMIT};
+  use ::alloc::format;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn elided_long_chain() {
+    let input = message_chain(&[
+      "job failed",
+      "upload failed",
+      "connection reset",
+      "handshake failed",
+      "permission denied",
+    ]);
+    let actual: String = input.display_elided(1).to_string();
+    let expected = String::from("job failed: ... (3 omitted) ...: permission denied");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_elided(2).to_string();
+    let expected = String::from("job failed: upload failed: ... (1 omitted) ...: handshake failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn elided_short_chain() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual: String = input.display_elided(1).to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+    let actual: String = input.display_elided(0).to_string();
+    let expected = String::from("... (2 omitted) ...");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn elided_over_limit() {
+    let actual: String = CyclicError.display_elided(1).to_string();
+    let omitted = usize::from(MESSAGE_LIMIT) - 2;
+    let expected = format!("cycle detected: ... ({omitted} omitted) ...: cycle detected");
+    assert_eq!(actual, expected);
+  }
+}
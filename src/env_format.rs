@@ -0,0 +1,127 @@
+//! Runtime selection of the report format, with an environment variable.
+
+use crate::report::fmt_pretty;
+use crate::{DisplayFullError, DisplayFullErrorJson, DisplayFullErrorTree, Error};
+use ::core::fmt;
+use ::std::sync::OnceLock;
+
+/// Format used by the reports and printing helpers.
+///
+/// The format can be selected at runtime with the `RUST_ERROR_FORMAT`
+/// environment variable, see [`ReportFormat::from_env`]. This allows operators
+/// to change the verbosity without recompiling. It applies to the `Debug`
+/// format of [`Report`](crate::Report) (used when returning it from `main`)
+/// and to [`eprint_full`](crate::DisplayFullErrorExt::eprint_full).
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReportFormat {
+  /// Only the message of the outermost error (`compact`).
+  Compact,
+  /// The full chain on a single line, as [`DisplayFullError`](struct@DisplayFullError) (`full`).
+  Full,
+  /// The message of the outermost error, followed by its sources on separate
+  /// lines (`pretty`).
+  Pretty,
+  /// The chain as a JSON array of strings, as [`DisplayFullErrorJson`] (`json`).
+  Json,
+  /// The chain as a tree with box-drawing connectors, as
+  /// [`DisplayFullErrorTree`] (`tree`).
+  Tree,
+}
+
+/// Name of the environment variable selecting the format.
+const ENV_VAR: &str = "RUST_ERROR_FORMAT";
+
+impl ReportFormat {
+  /// Get the format selected by the `RUST_ERROR_FORMAT` environment variable.
+  ///
+  /// The variable is read once, on the first call. Returns `None` if the
+  /// variable is unset or has an unknown value, in which case each helper uses
+  /// its default format.
+  pub fn from_env() -> Option<Self> {
+    static FORMAT: OnceLock<Option<ReportFormat>> = OnceLock::new();
+    *FORMAT.get_or_init(|| ::std::env::var(ENV_VAR).ok().and_then(|name| Self::from_name(&name)))
+  }
+
+  /// Get the format with the provided name: `compact`, `full`, `pretty`,
+  /// `json` or `tree`.
+  ///
+  /// ```rust
+  /// use ::display_full_error::ReportFormat;
+  ///
+  /// assert_eq!(ReportFormat::from_name("json"), Some(ReportFormat::Json));
+  /// assert_eq!(ReportFormat::from_name("verbose"), None);
+  /// ```
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name.trim() {
+      "compact" => Some(Self::Compact),
+      "full" => Some(Self::Full),
+      "pretty" => Some(Self::Pretty),
+      "json" => Some(Self::Json),
+      "tree" => Some(Self::Tree),
+      _ => None,
+    }
+  }
+
+  /// Get a formatter displaying `error` with this format.
+  pub fn display<E>(self, error: &E) -> DisplayReportFormat<'_, E>
+  where
+    E: Error + ?Sized,
+  {
+    DisplayReportFormat(error, self)
+  }
+}
+
+/// Formatting wrapper to display an error with a [`ReportFormat`].
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayReportFormat<'e, E>(pub &'e E, pub ReportFormat)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayReportFormat<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.1 {
+      ReportFormat::Compact => fmt::Display::fmt(self.0, f),
+      ReportFormat::Full => fmt::Display::fmt(&DisplayFullError(self.0), f),
+      ReportFormat::Pretty => fmt_pretty(self.0, f),
+      ReportFormat::Json => fmt::Display::fmt(&DisplayFullErrorJson(self.0), f),
+      ReportFormat::Tree => fmt::Display::fmt(&DisplayFullErrorTree(self.0), f),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ReportFormat;
+  use crate::tests::message_chain;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn display_each_format() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual = |format: ReportFormat| format.display(&input).to_string();
+    assert_eq!(actual(ReportFormat::Compact), String::from("upload failed"));
+    assert_eq!(
+      actual(ReportFormat::Full),
+      String::from("upload failed: permission denied")
+    );
+    assert_eq!(
+      actual(ReportFormat::Pretty),
+      String::from("upload failed\n\nCaused by:\n  0: permission denied")
+    );
+    assert_eq!(
+      actual(ReportFormat::Json),
+      String::from(r#"["upload failed","permission denied"]"#)
+    );
+    assert_eq!(
+      actual(ReportFormat::Tree),
+      String::from("upload failed\n└─ permission denied")
+    );
+  }
+}
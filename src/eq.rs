@@ -0,0 +1,59 @@
+//! Comparison of the formatted chain with strings, without allocating.
+
+use crate::{DisplayFullError, Error};
+use ::core::fmt::{self, Write};
+
+impl<E> PartialEq<str> for DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn eq(&self, other: &str) -> bool {
+    let mut expected = ExpectStr(other);
+    write!(expected, "{self}").is_ok() && expected.0.is_empty()
+  }
+}
+
+impl<E> PartialEq<&str> for DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn eq(&self, other: &&str) -> bool {
+    *self == **other
+  }
+}
+
+/// Writer matching the written strs against the start of the expected str,
+/// failing on the first difference.
+struct ExpectStr<'s>(&'s str);
+
+impl Write for ExpectStr<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.0 = self.0.strip_prefix(s).ok_or(fmt::Error)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+
+  #[test]
+  fn eq_str() {
+    let input = UploadError::Permission(PermissionError);
+    assert_eq!(input.display_full(), "upload failed: permission denied");
+    assert!(input.display_full() != "upload failed");
+    assert!(input.display_full() != "upload failed: permission denied.");
+    assert!(input.display_full() != "upload failed: permission");
+  }
+
+  #[test]
+  fn eq_str_multiple_writes() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    assert_eq!(
+      input.display_full(),
+      "upload failed: connection reset: permission denied"
+    );
+    assert!(input.display_full() != "upload failed: connection reset");
+  }
+}
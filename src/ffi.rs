@@ -0,0 +1,226 @@
+//! C FFI support, to pass error chains to C code.
+
+use crate::private::AsDynError;
+use crate::{DisplayFullError, Error};
+use ::core::ffi::c_char;
+use ::core::fmt::{self, Write};
+
+/// Format the full error chain into a byte buffer, as a NUL-terminated string.
+///
+/// This follows the semantics of `snprintf`: as many bytes as fit are written,
+/// followed by a NUL byte. The return value is the length of the full message
+/// (excluding the NUL byte), so the output was truncated if the return value is
+/// greater than or equal to `buf.len()`. If `buf` is empty, nothing is written.
+///
+/// Truncation never splits a UTF-8 character: the written bytes are always
+/// valid UTF-8.
+///
+/// If a `Display` implementation of the chain returns an error, the output
+/// written so far is NUL-terminated and [`FORMAT_ERROR`] (`usize::MAX`) is
+/// returned, as the length of the full message is unknown.
+pub fn format_full_into<E>(error: &E, buf: &mut [u8]) -> usize
+where
+  E: Error + ?Sized,
+{
+  let capacity = buf.len().saturating_sub(1);
+  let mut writer = BufWriter {
+    buf,
+    capacity,
+    written: 0,
+    full: false,
+    total: 0,
+  };
+  // `BufWriter` never fails, errors come from `Display` implementations
+  let result = write!(writer, "{}", DisplayFullError(error));
+  let BufWriter {
+    buf, written, total, ..
+  } = writer;
+  if let Some(nul) = buf.get_mut(written) {
+    *nul = 0;
+  }
+  match result {
+    Ok(()) => total,
+    Err(fmt::Error) => FORMAT_ERROR,
+  }
+}
+
+/// Value returned by [`format_full_into`] and [`display_full_error_format`]
+/// when a `Display` implementation of the chain fails.
+pub const FORMAT_ERROR: usize = usize::MAX;
+
+/// Opaque handle to an error chain, to pass it to C code.
+///
+/// C code can't build a reference to a Rust error. Instead, create the handle
+/// on the Rust side with [`ErrorHandle::new`], and pass a pointer to it (see
+/// [`as_ptr`](Self::as_ptr)). C code only uses the pointer, as an opaque type:
+///
+/// ```c
+/// typedef struct DisplayFullErrorHandle DisplayFullErrorHandle;
+///
+/// size_t display_full_error_format(const DisplayFullErrorHandle *error, char *buf, size_t len);
+/// ```
+///
+/// The handle borrows the error: the pointer is only valid while both the
+/// handle and the error are alive.
+///
+/// ```rust
+/// use ::display_full_error::{display_full_error_format, ErrorHandle};
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// let handle = ErrorHandle::new(&err);
+/// let mut buf = [0; 64];
+/// // SAFETY: `handle` and `buf` are valid for the duration of the call
+/// let len = unsafe { display_full_error_format(handle.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+/// assert_eq!(len, 17);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorHandle<'e>(&'e (dyn Error + 'static));
+
+impl<'e> ErrorHandle<'e> {
+  /// Create a handle to `error`.
+  pub fn new<E>(error: &'e E) -> Self
+  where
+    E: AsDynError + ?Sized,
+  {
+    Self(error.as_dyn_error())
+  }
+
+  /// Get a pointer to this handle, to pass to C code.
+  pub fn as_ptr(&self) -> *const Self {
+    self
+  }
+}
+
+/// Format the full error chain into a C buffer, as a NUL-terminated string.
+///
+/// This is the C entry point for [`format_full_into`], exported as
+/// `display_full_error_format`. The error is passed as an [`ErrorHandle`]
+/// created on the Rust side. It returns the length of the full message
+/// (excluding the NUL byte): the output was truncated if the return value is
+/// greater than or equal to `len`. It returns [`FORMAT_ERROR`] if a `Display`
+/// implementation fails. If `error` is null, the empty string is written.
+///
+/// # Safety
+///
+/// - `error` must be null or point to a valid [`ErrorHandle`] for the duration
+///   of the call.
+/// - `buf` must be valid for writes of `len` bytes. It may be null only if
+///   `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn display_full_error_format(
+  error: *const ErrorHandle<'_>,
+  buf: *mut c_char,
+  len: usize,
+) -> usize {
+  let buf: &mut [u8] = if buf.is_null() || len == 0 {
+    &mut []
+  } else {
+    // SAFETY: the caller guarantees that `buf` is valid for writes of `len` bytes
+    unsafe { ::core::slice::from_raw_parts_mut(buf.cast::<u8>(), len) }
+  };
+  // SAFETY: the caller guarantees that `error` is null or valid
+  match unsafe { error.as_ref() } {
+    Some(handle) => format_full_into(handle.0, buf),
+    None => {
+      if let Some(nul) = buf.first_mut() {
+        *nul = 0;
+      }
+      0
+    }
+  }
+}
+
+/// Writer filling a byte buffer, counting the bytes that did not fit.
+struct BufWriter<'b> {
+  buf: &'b mut [u8],
+  /// Number of bytes available for the message, excluding the NUL byte
+  capacity: usize,
+  written: usize,
+  /// Set once a string did not fit, to avoid writing shorter strings after it
+  full: bool,
+  total: usize,
+}
+
+impl Write for BufWriter<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.total = self.total.saturating_add(s.len());
+    if self.full {
+      return Ok(());
+    }
+    let available = self.capacity.saturating_sub(self.written);
+    let mut len = s.len().min(available);
+    while !s.is_char_boundary(len) {
+      len = len.saturating_sub(1);
+    }
+    if len < s.len() {
+      self.full = true;
+    }
+    let end = self.written.saturating_add(len);
+    if let (Some(dst), Some(src)) = (self.buf.get_mut(self.written..end), s.as_bytes().get(..len)) {
+      dst.copy_from_slice(src);
+      self.written = end;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{PermissionError, UploadError};
+
+  #[test]
+  fn format_into_large_buffer() {
+    let input = UploadError::Permission(PermissionError);
+    let mut buf = [0xffu8; 64];
+    let len = format_full_into(&input, &mut buf);
+    assert_eq!(len, 32);
+    assert_eq!(&buf[..=len], b"upload failed: permission denied\0");
+  }
+
+  #[test]
+  fn format_into_small_buffer() {
+    let input = UploadError::Permission(PermissionError);
+    let mut buf = [0xffu8; 16];
+    let len = format_full_into(&input, &mut buf);
+    assert_eq!(len, 32);
+    assert_eq!(&buf, b"upload failed: \0");
+  }
+
+  #[test]
+  fn format_from_c() {
+    let input = UploadError::Permission(PermissionError);
+    let handle = ErrorHandle::new(&input);
+    let mut buf = [0 as c_char; 8];
+    // SAFETY: `handle` and `buf` are valid for the duration of the call
+    let len = unsafe { display_full_error_format(handle.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(len, 32);
+    // SAFETY: the buffer is NUL-terminated
+    let actual = unsafe { ::core::ffi::CStr::from_ptr(buf.as_ptr()) };
+    assert_eq!(actual.to_bytes(), b"upload ");
+  }
+
+  #[test]
+  fn format_error_sentinel() {
+    #[derive(Debug)]
+    struct BrokenError;
+
+    impl fmt::Display for BrokenError {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("broken")?;
+        Err(fmt::Error)
+      }
+    }
+
+    impl Error for BrokenError {}
+
+    let mut buf = [0xffu8; 16];
+    assert_eq!(format_full_into(&BrokenError, &mut buf), FORMAT_ERROR);
+    assert_eq!(&buf[..7], b"broken\0");
+    let handle = ErrorHandle::new(&BrokenError);
+    let mut buf = [0 as c_char; 16];
+    // SAFETY: `handle` and `buf` are valid for the duration of the call
+    let len = unsafe { display_full_error_format(handle.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(len, FORMAT_ERROR);
+  }
+}
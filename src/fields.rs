@@ -0,0 +1,184 @@
+//! Key/value fields attached to the errors of a chain, for structured outputs.
+
+use crate::json::JsonEscape;
+use crate::private::AsDynError;
+use crate::{chain_len, Chain, Error};
+use ::core::fmt::{self, Write};
+
+/// Key/value pairs describing an error, in addition to its message.
+///
+/// Implement this trait on your errors to expose context such as
+/// `("bucket", "photos")` to the structured outputs, which include the fields
+/// with the message of the error. Since the sources of a chain are only
+/// available as `&dyn Error`, the outputs find the implementations with a
+/// [`FieldsLookup`], usually built with [`fields_of`].
+///
+/// The fields are included by [`DisplayFullErrorJsonFields`] and by the
+/// [`NdjsonReporter`](crate::NdjsonReporter), see its
+/// [`fields`](crate::NdjsonReporter::fields) method. There is no logfmt,
+/// journald or Sentry output yet. The `message` key is reserved: a field with
+/// this key is skipped.
+///
+/// ```rust
+/// use ::display_full_error::{fields_of, ChainFields, DisplayFullErrorExt};
+/// use ::std::fmt;
+///
+/// #[derive(Debug)]
+/// struct UploadError {
+///   bucket: &'static str,
+/// }
+///
+/// impl fmt::Display for UploadError {
+///   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///     f.write_str("upload failed")
+///   }
+/// }
+///
+/// impl ::std::error::Error for UploadError {}
+///
+/// impl ChainFields for UploadError {
+///   fn fields(&self, visit: &mut dyn FnMut(&str, &dyn fmt::Display) -> fmt::Result) -> fmt::Result {
+///     visit("bucket", &self.bucket)
+///   }
+/// }
+///
+/// let err = UploadError { bucket: "photos" };
+/// assert_eq!(
+///   err.display_json_fields(&fields_of::<UploadError>).to_string(),
+///   r#"[{"message":"upload failed","bucket":"photos"}]"#
+/// );
+/// ```
+pub trait ChainFields {
+  /// Call `visit` with each key and value.
+  ///
+  /// Errors returned by `visit` must be forwarded.
+  fn fields(&self, visit: &mut dyn FnMut(&str, &dyn fmt::Display) -> fmt::Result) -> fmt::Result;
+}
+
+/// Function finding the [`ChainFields`] implementation of an error of the
+/// chain, if any.
+///
+/// Combine [`fields_of`] for each type implementing `ChainFields`, e.g.
+/// `fields_of::<UploadError>(e).or_else(|| fields_of::<QueryError>(e))`.
+pub type FieldsLookup<'a> = dyn for<'e> Fn(&'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields> + 'a;
+
+/// Get the [`ChainFields`] of `error` if it has the type `T`.
+///
+/// This is the building block of a [`FieldsLookup`].
+pub fn fields_of<'e, T>(error: &'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields>
+where
+  T: ChainFields + Error + 'static,
+{
+  error.downcast_ref::<T>().map(|e| e as &dyn ChainFields)
+}
+
+/// Formatting wrapper to display the error chain as a JSON array of objects,
+/// with the fields of each error.
+///
+/// Each error of the chain is an object, starting with the outermost error.
+/// The object has a `message` member, followed by the
+/// [fields](ChainFields) found by the [lookup](FieldsLookup), except the
+/// fields with the reserved `message` key. Keys and values
+/// are escaped as specified by
+/// [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259), values are always
+/// strings. The chain is printed up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT)
+/// messages, if it is longer the array ends with a `{"message":"..."}` object.
+#[derive(Clone, Copy)]
+pub struct DisplayFullErrorJsonFields<'e, E>(pub &'e E, pub &'e FieldsLookup<'e>)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Debug for DisplayFullErrorJsonFields<'_, E>
+where
+  E: Error + ?Sized + fmt::Debug,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("DisplayFullErrorJsonFields")
+      .field(&self.0)
+      .field(&"Fn")
+      .finish()
+  }
+}
+
+impl<E> fmt::Display for DisplayFullErrorJsonFields<'_, E>
+where
+  E: Error + AsDynError + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('[')?;
+    for (i, e) in Chain::new(self.0.as_dyn_error()).enumerate() {
+      if i > 0 {
+        f.write_char(',')?;
+      }
+      f.write_str("{\"message\":\"")?;
+      write!(JsonEscape(&mut *f), "{e}")?;
+      f.write_char('"')?;
+      write_json_fields(f, self.1, e, true)?;
+      f.write_char('}')?;
+    }
+    if chain_len(self.0).1 {
+      f.write_str(",{\"message\":\"...\"}")?;
+    }
+    f.write_char(']')
+  }
+}
+
+/// Key of the message member of the JSON objects, which the fields can't use.
+const MESSAGE_KEY: &str = "message";
+
+/// Write the fields of `error` found by `lookup` as JSON object members.
+///
+/// If `comma` is `true`, the first member is preceded by a comma, so the fields
+/// can follow other members.
+pub(crate) fn write_json_fields(
+  f: &mut fmt::Formatter<'_>,
+  lookup: &FieldsLookup<'_>,
+  error: &(dyn Error + 'static),
+  mut comma: bool,
+) -> fmt::Result {
+  let Some(fields) = lookup(error) else {
+    return Ok(());
+  };
+  fields.fields(&mut |key, value| {
+    if key == MESSAGE_KEY {
+      return Ok(());
+    }
+    if comma {
+      f.write_char(',')?;
+    }
+    comma = true;
+    f.write_char('"')?;
+    JsonEscape(&mut *f).write_str(key)?;
+    f.write_str("\":\"")?;
+    write!(JsonEscape(&mut *f), "{value}")?;
+    f.write_char('"')
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{fields_of, ChainFields};
+  use crate::tests::{message_chain, MessageError};
+  use crate::{DisplayFullErrorExt, Error};
+  use ::alloc::string::{String, ToString};
+  use ::core::fmt;
+
+  impl ChainFields for MessageError {
+    fn fields(&self, visit: &mut dyn FnMut(&str, &dyn fmt::Display) -> fmt::Result) -> fmt::Result {
+      visit("len", &self.to_string().len())?;
+      visit("message", &"reserved")?;
+      visit("quote\"", &"a\"b")
+    }
+  }
+
+  #[test]
+  fn json_fields_per_level() {
+    fn only_root<'e>(e: &'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields> {
+      fields_of::<MessageError>(e).filter(|_| e.source().is_none())
+    }
+    let input = message_chain(&["upload failed", "denied"]);
+    let actual: String = input.display_json_fields(&only_root).to_string();
+    let expected = String::from(r#"[{"message":"upload failed"},{"message":"denied","len":"6","quote\"":"a\"b"}]"#);
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,74 @@
+//! Fingerprint of a formatted error chain.
+
+use crate::{DisplayFullErrorWith, Error, FormatOptions};
+use ::core::fmt::{self, Write};
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Writer computing the 64-bit FNV-1a hash of the written text.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+  pub(crate) const fn new() -> Self {
+    Self(FNV_OFFSET_BASIS)
+  }
+
+  pub(crate) const fn finish(&self) -> u64 {
+    self.0
+  }
+
+  pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+    for &byte in bytes {
+      self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+    }
+  }
+}
+
+impl Write for Fnv1a {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.write_bytes(s.as_bytes());
+    Ok(())
+  }
+}
+
+/// Compute the fingerprint of the full chain of `error`.
+pub(crate) fn fingerprint<E>(error: &E) -> u64
+where
+  E: Error + ?Sized,
+{
+  let mut hasher = Fnv1a::new();
+  // Writing to the hasher never fails
+  let _ = write!(hasher, "{}", DisplayFullErrorWith(error, FormatOptions::new()));
+  hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Fnv1a;
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::core::fmt::Write;
+
+  #[test]
+  fn fnv1a_reference_values() {
+    let mut empty = Fnv1a::new();
+    let _ = empty.write_str("");
+    assert_eq!(empty.finish(), 0xcbf2_9ce4_8422_2325);
+    let mut hasher = Fnv1a::new();
+    let _ = hasher.write_str("a");
+    assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+  }
+
+  #[test]
+  fn fingerprint_depends_on_full_chain() {
+    let first = message_chain(&["upload failed", "permission denied"]);
+    let second = message_chain(&["upload failed", "permission denied"]);
+    let other = message_chain(&["upload failed", "upload exceeds max limit"]);
+    assert_eq!(first.fingerprint(), second.fingerprint());
+    assert_ne!(first.fingerprint(), other.fingerprint());
+  }
+}
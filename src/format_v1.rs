@@ -0,0 +1,93 @@
+//! Version 1 of the output format, pinned.
+//!
+//! This is the current format of the crate root
+//! [`DisplayFullError`](struct@crate::DisplayFullError): messages on a single
+//! line, separated with `: `, and a single `: ...` after
+//! [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages. The format of this module
+//! never changes. If the formatting is improved in a future version, the new
+//! format will ship in a new module (e.g. `format_v2`) and the wrapper of this
+//! module will keep producing the same bytes. Import from this module to pin
+//! the exact format you rely on, for example in snapshot tests.
+//!
+//! The only exception is the cut-off of long chains: [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT)
+//! depends on the `limit-*` features, and cargo enables a feature for the
+//! whole dependency graph when any crate requests it. Chains of up to 64
+//! messages are not affected.
+//!
+//! ```rust
+//! use ::display_full_error::format_v1;
+//!
+//! let err = ::std::io::Error::other("permission denied");
+//! assert_eq!(format_v1::display_full(&err).to_string(), "permission denied");
+//! ```
+
+use crate::{sources, Error, MESSAGE_LIMIT};
+use ::core::fmt;
+
+/// Formatting wrapper to display errors, including their sources, in the
+/// version 1 format.
+///
+/// Unlike the crate root [`DisplayFullError`](struct@crate::DisplayFullError),
+/// this wrapper keeps this format if the default format changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullError<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt_full(self.0, f)
+  }
+}
+
+/// Wrap `error` to display it with its sources, in the version 1 format.
+pub fn display_full<E>(error: &E) -> DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  DisplayFullError(error)
+}
+
+/// Write `error` with its sources, in the version 1 format.
+fn fmt_full<E>(error: &E, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  fmt::Display::fmt(error, f)?;
+  let mut printed: u16 = 1;
+  for e in sources(error) {
+    if printed >= MESSAGE_LIMIT {
+      f.write_str(": ...")?;
+      return Ok(());
+    }
+    f.write_str(": ")?;
+    fmt::Display::fmt(e, f)?;
+    printed = printed.saturating_add(1);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn same_as_display_full() {
+    let input = UploadError::Permission(PermissionError);
+    assert_eq!(
+      super::display_full(&input).to_string(),
+      input.display_full().to_string()
+    );
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let actual: String = super::display_full(&input).to_string();
+    assert_eq!(
+      actual,
+      String::from("upload failed: connection reset: permission denied")
+    );
+  }
+}
@@ -0,0 +1,64 @@
+//! Head display mode, printing only the outermost messages of the chain.
+
+use crate::{sources, Error, MESSAGE_LIMIT};
+use ::core::fmt;
+
+/// Formatting wrapper to display only the outermost messages of an error chain.
+///
+/// The second field is the maximum number of messages to print, capped to
+/// [`MESSAGE_LIMIT`]. Messages are formatted on a single line, separated with
+/// `: `. If inner messages are omitted, the output ends with `: ...`.
+///
+/// This is useful for compact outputs such as UI notifications, where only the
+/// top-level context matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorHead<'e, E>(pub &'e E, pub usize)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorHead<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let limit = self.1.min(usize::from(MESSAGE_LIMIT));
+    if limit == 0 {
+      return f.write_str("...");
+    }
+    fmt::Display::fmt(&self.0, f)?;
+    let mut printed: usize = 1;
+    for e in sources(self.0) {
+      if printed >= limit {
+        f.write_str(": ...")?;
+        return Ok(());
+      }
+      f.write_str(": ")?;
+      fmt::Display::fmt(e, f)?;
+      printed = printed.saturating_add(1);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn head_of_long_chain() {
+    let input = message_chain(&["job failed", "upload failed", "connection reset", "permission denied"]);
+    let actual: String = input.display_head(2).to_string();
+    let expected = String::from("job failed: upload failed: ...");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn head_of_short_chain() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual: String = input.display_head(2).to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+}
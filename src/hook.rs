@@ -0,0 +1,392 @@
+//! Global hook customizing the reports and printing helpers.
+
+use crate::report::fmt_pretty_sources_styled;
+use crate::tree::fmt_tree_sources;
+use crate::{sources, DisplayFullErrorJson, Error, ReportFormat, MESSAGE_LIMIT};
+use ::core::fmt;
+use ::std::sync::OnceLock;
+
+/// Hook installed with [`set_hook`].
+static HOOK: OnceLock<ReportHook> = OnceLock::new();
+
+/// ANSI escape sequence resetting the style.
+pub(crate) const STYLE_END: &str = "\x1b[0m";
+
+/// Severity of a message of the chain, see [`ReportHook::severity`].
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  /// Highlighted in bold red.
+  Error,
+  /// Highlighted in yellow.
+  Warning,
+  /// Not highlighted.
+  Info,
+  /// Dimmed.
+  Debug,
+}
+
+impl Severity {
+  /// Get the name of the severity, in lowercase: `error`, `warning`, `info`
+  /// or `debug`.
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+      Severity::Info => "info",
+      Severity::Debug => "debug",
+    }
+  }
+
+  /// Get the ANSI escape sequence starting the style of this severity.
+  const fn style(self) -> Option<&'static str> {
+    match self {
+      Severity::Error => Some("\x1b[1;31m"),
+      Severity::Warning => Some("\x1b[33m"),
+      Severity::Info => None,
+      Severity::Debug => Some("\x1b[2m"),
+    }
+  }
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// Indentation of the sources in the multiline ([`Pretty`](ReportFormat::Pretty))
+/// format, see [`ReportHook::indent`].
+///
+/// Each source line starts with the indentation unit, repeated for each level
+/// of the chain if the indentation is nested. The default is a flat
+/// indentation of two spaces.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::Indent;
+///
+/// // No leading whitespace, for collectors stripping it
+/// const FLAT: Indent = Indent::new("");
+/// // One tab per level
+/// const NESTED: Indent = Indent::new("\t").nested(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Indent {
+  unit: &'static str,
+  nested: bool,
+}
+
+impl Indent {
+  /// Create a flat indentation using `unit`, such as `"  "` or `"\t"`.
+  pub const fn new(unit: &'static str) -> Self {
+    Self { unit, nested: false }
+  }
+
+  /// Repeat the indentation unit for each level of the chain, instead of
+  /// using it once for every source.
+  pub const fn nested(mut self, nested: bool) -> Self {
+    self.nested = nested;
+    self
+  }
+
+  /// Write the indentation of the source at `depth` (`1` is the first source).
+  pub(crate) fn write_to<W>(self, out: &mut W, depth: usize) -> fmt::Result
+  where
+    W: fmt::Write + ?Sized,
+  {
+    let count = if self.nested { depth } else { 1 };
+    for _ in 0..count {
+      out.write_str(self.unit)?;
+    }
+    Ok(())
+  }
+}
+
+impl Default for Indent {
+  fn default() -> Self {
+    Self::new("  ")
+  }
+}
+
+/// Global configuration of the reports and printing helpers.
+///
+/// The hook is installed once for the whole program with [`set_hook`], so
+/// applications configure the formatting centrally instead of at every call
+/// site. It applies to the `Debug` format of [`Report`](crate::Report) and to
+/// [`eprint_full`](crate::DisplayFullErrorExt::eprint_full). The
+/// [indentation](ReportHook::indent) also applies to the alternate `Display`
+/// format of `Report` (`{:#}`). Formatting
+/// wrappers such as [`DisplayFullError`](struct@crate::DisplayFullError) are
+/// not affected.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{set_hook, ReportFormat, ReportHook};
+///
+/// const HOOK: ReportHook = ReportHook::new().separator(" <- ").format(ReportFormat::Full);
+/// set_hook(HOOK).expect("the hook is only set once");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ReportHook {
+  separator: &'static str,
+  color: bool,
+  format: Option<ReportFormat>,
+  severity: Option<fn(usize) -> Severity>,
+  indent: Indent,
+}
+
+impl ReportHook {
+  /// Create the default hook, matching the behavior when no hook is set.
+  pub const fn new() -> Self {
+    Self {
+      separator: ": ",
+      color: false,
+      format: None,
+      severity: None,
+      indent: Indent::new("  "),
+    }
+  }
+
+  /// Set the separator between messages of the [`Full`](ReportFormat::Full)
+  /// format. The default is `: `.
+  pub const fn separator(mut self, separator: &'static str) -> Self {
+    self.separator = separator;
+    self
+  }
+
+  /// Highlight the messages with ANSI escape sequences, according to their
+  /// [severity](ReportHook::severity). By default, only the outermost message
+  /// is highlighted. This has no effect on the [`Json`](ReportFormat::Json)
+  /// format.
+  pub const fn color(mut self, enabled: bool) -> Self {
+    self.color = enabled;
+    self
+  }
+
+  /// Assign a severity to each message, from its depth in the chain (`0` is
+  /// the outermost error).
+  ///
+  /// The severity is honored by the [colored](ReportHook::color) output and by
+  /// the [`NdjsonReporter`](crate::NdjsonReporter), so dashboards can
+  /// de-emphasize plumbing layers. By default, the outermost message is an
+  /// [`Error`](Severity::Error) and the sources are [`Info`](Severity::Info).
+  ///
+  /// ```rust
+  /// use ::display_full_error::{ReportHook, Severity};
+  ///
+  /// fn severity(depth: usize) -> Severity {
+  ///   match depth {
+  ///     0 => Severity::Error,
+  ///     1 => Severity::Warning,
+  ///     _ => Severity::Debug,
+  ///   }
+  /// }
+  ///
+  /// const HOOK: ReportHook = ReportHook::new().color(true).severity(severity);
+  /// ```
+  pub const fn severity(mut self, severity: fn(usize) -> Severity) -> Self {
+    self.severity = Some(severity);
+    self
+  }
+
+  /// Get the severity of the message at `depth`.
+  fn severity_at(&self, depth: usize) -> Severity {
+    match self.severity {
+      Some(severity) => severity(depth),
+      None if depth == 0 => Severity::Error,
+      None => Severity::Info,
+    }
+  }
+
+  /// Get the ANSI style of the message at `depth`, if colors are enabled.
+  fn style_at(&self, depth: usize) -> Option<&'static str> {
+    if self.color {
+      self.severity_at(depth).style()
+    } else {
+      None
+    }
+  }
+
+  /// Set the indentation of the sources in the multiline
+  /// ([`Pretty`](ReportFormat::Pretty)) format. The default is a flat
+  /// indentation of two spaces.
+  pub const fn indent(mut self, indent: Indent) -> Self {
+    self.indent = indent;
+    self
+  }
+
+  /// Set the format used by all the helpers, instead of their default.
+  ///
+  /// The `RUST_ERROR_FORMAT` environment variable still takes precedence, so
+  /// operators can change the verbosity at runtime.
+  pub const fn format(mut self, format: ReportFormat) -> Self {
+    self.format = Some(format);
+    self
+  }
+}
+
+impl Default for ReportHook {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Error returned by [`set_hook`] when a hook is already installed.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SetHookError;
+
+impl fmt::Display for SetHookError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("report hook is already set")
+  }
+}
+
+impl Error for SetHookError {}
+
+/// Install the global [`ReportHook`].
+///
+/// The hook can only be set once, further calls return an error.
+///
+/// Requires the `std` feature.
+pub fn set_hook(hook: ReportHook) -> Result<(), SetHookError> {
+  HOOK.set(hook).map_err(|_| SetHookError)
+}
+
+/// Get the installed hook, or the default hook.
+fn hook() -> ReportHook {
+  HOOK.get().copied().unwrap_or_default()
+}
+
+/// Get the indentation of the multiline format with the installed hook.
+pub(crate) fn indent() -> Indent {
+  hook().indent
+}
+
+/// Get the severity of the message at `depth` with the installed hook.
+pub(crate) fn severity_at(depth: usize) -> Severity {
+  hook().severity_at(depth)
+}
+
+/// Write `message` with the ANSI `style`, if any.
+fn write_styled<M>(f: &mut fmt::Formatter<'_>, style: Option<&str>, message: &M) -> fmt::Result
+where
+  M: fmt::Display + ?Sized,
+{
+  match style {
+    Some(style) => write!(f, "{style}{message}{STYLE_END}"),
+    None => fmt::Display::fmt(message, f),
+  }
+}
+
+/// Get the format to use by a helper with the provided default format.
+///
+/// The environment variable has priority over the hook.
+pub(crate) fn resolve_format(default: ReportFormat) -> ReportFormat {
+  ReportFormat::from_env().or(hook().format).unwrap_or(default)
+}
+
+/// Formatting wrapper applying the installed hook with a resolved format.
+pub(crate) struct DisplayHooked<'e, E>(pub(crate) &'e E, pub(crate) ReportFormat)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayHooked<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let hook = hook();
+    if self.1 == ReportFormat::Json {
+      return fmt::Display::fmt(&DisplayFullErrorJson(self.0), f);
+    }
+    write_styled(f, hook.style_at(0), self.0)?;
+    match self.1 {
+      ReportFormat::Full => {
+        let mut printed: u16 = 1;
+        for e in sources(self.0) {
+          f.write_str(hook.separator)?;
+          if printed >= MESSAGE_LIMIT {
+            return f.write_str("...");
+          }
+          write_styled(f, hook.style_at(usize::from(printed)), e)?;
+          printed = printed.saturating_add(1);
+        }
+        Ok(())
+      }
+      ReportFormat::Pretty => fmt_pretty_sources_styled(self.0, f, hook.indent, |depth| hook.style_at(depth)),
+      ReportFormat::Tree => fmt_tree_sources(self.0, f, |f, depth, e| write_styled(f, hook.style_at(depth), e)),
+      ReportFormat::Compact | ReportFormat::Json => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{DisplayHooked, Indent, ReportHook, Severity};
+  use crate::tests::message_chain;
+  use crate::ReportFormat;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn default_hook_formats() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual = DisplayHooked(&input, ReportFormat::Full).to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+    let actual = DisplayHooked(&input, ReportFormat::Pretty).to_string();
+    assert_eq!(
+      actual,
+      String::from("upload failed\n\nCaused by:\n  0: permission denied")
+    );
+  }
+
+  #[test]
+  fn hook_builder() {
+    let hook = ReportHook::new()
+      .separator(" <- ")
+      .color(true)
+      .format(ReportFormat::Compact);
+    assert_eq!(hook.separator, " <- ");
+    assert!(hook.color);
+    assert_eq!(hook.format, Some(ReportFormat::Compact));
+    let default = ReportHook::default();
+    assert_eq!(default.separator, ": ");
+    assert!(!default.color);
+    assert_eq!(default.format, None);
+    assert!(default.severity.is_none());
+    assert_eq!(default.indent, Indent::new("  "));
+  }
+
+  #[test]
+  fn severity_styles() {
+    fn severity(depth: usize) -> Severity {
+      match depth {
+        0 => Severity::Error,
+        1 => Severity::Info,
+        _ => Severity::Debug,
+      }
+    }
+    let hook = ReportHook::new().color(true);
+    assert_eq!(hook.style_at(0), Some("\x1b[1;31m"));
+    assert_eq!(hook.style_at(1), None);
+    let hook = hook.severity(severity);
+    assert_eq!(hook.severity_at(2), Severity::Debug);
+    assert_eq!(hook.style_at(2), Some("\x1b[2m"));
+    assert_eq!(hook.color(false).style_at(0), None);
+  }
+
+  #[test]
+  fn indent_prefix() {
+    let mut actual = String::new();
+    assert_eq!(Indent::new("\t").nested(true).write_to(&mut actual, 3), Ok(()));
+    assert_eq!(actual, "\t\t\t");
+    let mut actual = String::new();
+    assert_eq!(Indent::default().write_to(&mut actual, 3), Ok(()));
+    assert_eq!(actual, "  ");
+  }
+}
@@ -0,0 +1,47 @@
+//! Conversion of chains to `io::Error`, for APIs returning I/O errors.
+
+use crate::{DisplayFullError, Error};
+use ::std::io;
+use ::std::string::ToString;
+
+/// Flatten the chain of `error` into an [`io::Error`] whose message is the
+/// full chain.
+///
+/// The returned error has the kind [`io::ErrorKind::Other`] and no source: the
+/// message is formatted as [`DisplayFullError`](struct@DisplayFullError), so
+/// the causes are kept even when the caller only prints the message of the
+/// `io::Error`. This is intended for APIs which force `io::Error` returns,
+/// such as `io::Read` implementations.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::into_io_error_full;
+///
+/// let err: Result<u8, _> = "x".parse::<u8>();
+/// let io_err = into_io_error_full(err.unwrap_err());
+/// assert_eq!(io_err.kind(), ::std::io::ErrorKind::Other);
+/// assert_eq!(io_err.to_string(), "invalid digit found in string");
+/// ```
+pub fn into_io_error_full<E>(error: E) -> io::Error
+where
+  E: Error,
+{
+  io::Error::new(io::ErrorKind::Other, DisplayFullError(&error).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::into_io_error_full;
+  use crate::tests::message_chain;
+  use ::alloc::string::ToString;
+
+  #[test]
+  fn flatten_chain() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual = into_io_error_full(input);
+    assert_eq!(actual.kind(), ::std::io::ErrorKind::Other);
+    assert_eq!(actual.to_string(), "upload failed: permission denied");
+    assert!(::std::error::Error::source(&actual).is_none());
+  }
+}
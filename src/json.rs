@@ -0,0 +1,102 @@
+//! JSON output mode, printing the chain as an array of strings.
+
+use crate::{printed_sources, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the error chain as a JSON array of strings.
+///
+/// Each message of the chain is a string of the array, starting with the
+/// outermost error. Messages are escaped as specified by
+/// [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259). The chain is printed up
+/// to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the
+/// array ends with a `"..."` string.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("invalid \"name\"");
+/// assert_eq!(err.display_json().to_string(), r#"["invalid \"name\""]"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorJson<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorJson<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[\"")?;
+    write!(JsonEscape(&mut *f), "{}", self.0)?;
+    f.write_char('"')?;
+    for source in printed_sources(self.0) {
+      f.write_str(",\"")?;
+      write!(JsonEscape(&mut *f), "{source}")?;
+      f.write_char('"')?;
+    }
+    f.write_char(']')
+  }
+}
+
+/// Writer escaping the text written to the inner writer as the content of a
+/// JSON string.
+///
+/// All the control chars and the chars treated as line breaks by YAML are
+/// escaped as well, so the output is also the content of a valid TOML basic
+/// string or YAML double-quoted scalar.
+pub(crate) struct JsonEscape<W>(pub(crate) W);
+
+impl<W> Write for JsonEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let mut rest = s;
+    while let Some(index) = rest.find(needs_escape) {
+      let (text, special) = rest.split_at(index);
+      self.0.write_str(text)?;
+      let mut chars = special.chars();
+      match chars.next() {
+        Some('"') => self.0.write_str("\\\"")?,
+        Some('\\') => self.0.write_str("\\\\")?,
+        Some('\n') => self.0.write_str("\\n")?,
+        Some('\r') => self.0.write_str("\\r")?,
+        Some('\t') => self.0.write_str("\\t")?,
+        Some(c) => write!(self.0, "\\u{:04x}", u32::from(c))?,
+        None => {}
+      }
+      rest = chars.as_str();
+    }
+    self.0.write_str(rest)
+  }
+}
+
+/// Check if `c` must be escaped: quotes, backslashes, control chars, and the
+/// chars outside of the YAML printable set or treated as line breaks.
+fn needs_escape(c: char) -> bool {
+  matches!(c, '"' | '\\' | '\u{2028}' | '\u{2029}' | '\u{FEFF}') || c.is_control()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn json_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_json().to_string();
+    let expected = String::from(r#"["upload failed","permission denied"]"#);
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn json_escapes_messages() {
+    let input = message_chain(&["path C:\\tmp\\\"a\"", "line 1\nline 2\u{1}"]);
+    let actual: String = input.display_json().to_string();
+    let expected = String::from(r#"["path C:\\tmp\\\"a\"","line 1\nline 2\u0001"]"#);
+    assert_eq!(actual, expected);
+  }
+}
@@ -1,15 +1,17 @@
 //! Display Full Error - Minimal display formatter for error chains
 //!
-//! This library provides the [`DisplayFullError`] wrapper type to format
+//! This library provides the [`DisplayFullError`](struct@DisplayFullError) wrapper type to format
 //! [errors](::core::error::Error) with their chain of
 //! [sources](core::error::Error::source).
 //!
 //! Error messages are formatted on a single line, separated with `: `; up to
-//! 1024 messages per chain are printed, after which a single `: ...` is printed.
+//! 1024 messages per chain are printed (see [`MESSAGE_LIMIT`]), after which a
+//! single `: ...` is printed.
 //!
-//! That's all there is to it, there is no extra configuration or advanced
-//! features. This is intended as the most minimal formatter supporting error
-//! sources, to address the fact that there's no helper in the standard library
+//! The default format has no configuration. When needed, the output can be
+//! customized with [`FormatOptions`], passed to
+//! [`display_full_with`](DisplayFullErrorExt::display_full_with). This is
+//! intended as a minimal formatter supporting error sources, to address the fact that there's no helper in the standard library
 //! so far as of Rust 1.85 (2025-03). If a standard formatter supporting error
 //! sources is added, this crate will be deprecated (but remain available).
 //!
@@ -78,12 +80,107 @@
 //! assert_eq!(err.to_string_full(), String::from("upload failed: permission denied"));
 //! ```
 //!
-//! This library requires Rust 1.81.0 or later as it depends on the Rust
-//! feature `error_in_core`. This library is compatible with `no_std`. There
-//! are no dependencies or optional features. This library does not introduce
-//! any runtime panics. It is recommended to use this library as an internal
-//! helper and to avoid leaking it into your public APIs. The output is
-//! guaranteed to be stable, any change would cause a major version bump.
+//! This library requires Rust 1.81.0 or later as it depends on the Rust feature
+//! `error_in_core`. This library is compatible with `no_std`. There are no
+//! dependencies outside of the optional derive macro. The library code avoids
+//! operations which may panic, such as indexing or unchecked arithmetic; this
+//! is checked by denying the corresponding Clippy lints in CI, not by the
+//! compiler, so it is not a build-time guarantee. Formatting also calls the
+//! `Display` implementations of your errors and the output writer, which may
+//! panic on their own, and the
+//! [`detect_double_print`](FormatOptions::detect_double_print) option
+//! deliberately panics in debug builds. It is
+//! recommended to use this library as an internal helper and to avoid leaking
+//! it into your public APIs. The output is guaranteed to be stable, any change
+//! would cause a major version bump. The [`format_v1`] module pins the current
+//! format explicitly, so it stays available if a later format is introduced.
+//!
+//! With the `derive` feature, this library also exposes the
+//! [`DisplayFullError`](macro@DisplayFullError) derive macro. It generates a
+//! `Display` implementation printing only the local message of the error, and
+//! an `Error` implementation returning the field marked with `#[source]` (or
+//! named `source`). Including the source in the message is rejected at compile
+//! time, as it would be printed twice by the chain formatter.
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use ::display_full_error::{DisplayFullError, DisplayFullErrorExt};
+//!
+//! #[derive(Debug, DisplayFullError)]
+//! enum UploadError {
+//!   #[display("upload failed")]
+//!   Permission(#[source] PermissionError),
+//!   #[display("upload of {size} bytes failed")]
+//!   Limit { size: u64, source: LimitError },
+//! }
+//!
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("permission denied")]
+//! struct PermissionError;
+//!
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload exceeds max limit")]
+//! struct LimitError;
+//!
+//! let err = UploadError::Limit { size: 100, source: LimitError };
+//! assert_eq!(err.display_full().to_string(), "upload of 100 bytes failed: upload exceeds max limit");
+//! # }
+//! ```
+//!
+//! ```rust,compile_fail
+//! # #[cfg(not(feature = "derive"))]
+//! # compile_error!("requires the `derive` feature");
+//! # use ::display_full_error::DisplayFullError;
+//! # #[derive(Debug, DisplayFullError)]
+//! # #[display("permission denied")]
+//! # struct PermissionError;
+//! // error: the message must not include the source
+//! #[derive(Debug, DisplayFullError)]
+//! #[display("upload failed: {source}")]
+//! struct UploadError {
+//!   source: PermissionError,
+//! }
+//! ```
+//!
+//! The [`prelude`] module re-exports the extension traits and formatting
+//! wrappers, so a single `use ::display_full_error::prelude::*;` is enough.
+//!
+//! # Cargo features
+//!
+//! - `alloc`: add the [`to_string_full`](DisplayFullErrorExt::to_string_full)
+//!   and [`to_cstring_full`](DisplayFullErrorExt::to_cstring_full) methods, the
+//!   [`DisplayPanicPayload`] formatter and the
+//!   [`diff_chain`](DisplayFullErrorExt::diff_chain) comparison.
+//! - `derive`: add the [`DisplayFullError`](macro@DisplayFullError) derive macro.
+//! - `ffi`: add [`format_full_into`] and its C entry point [`display_full_error_format`],
+//!   taking an [`ErrorHandle`].
+//! - `limit-64`, `limit-256`, `limit-4096`: change the [`MESSAGE_LIMIT`], for
+//!   constrained log sinks or very deep chains. The highest enabled limit wins.
+//! - `std`: add the integrations with the standard library. Implies `alloc`.
+//!   - Output to [`io::Write`](std::io::Write) with
+//!     [`write_full_to`](DisplayFullErrorExt::write_full_to), and printing to
+//!     stderr with [`eprint_full`](DisplayFullErrorExt::eprint_full).
+//!   - Formatting in a thread-local buffer with
+//!     [`to_string_full_cached`](DisplayFullErrorExt::to_string_full_cached).
+//!   - [`Report`], capturing a backtrace and implementing
+//!     [`Termination`](std::process::Termination), and [`CrashReport`].
+//!   - OS error enrichment with [`has_io_kind`](DisplayFullErrorExt::has_io_kind)
+//!     and [`find_raw_os_error`](DisplayFullErrorExt::find_raw_os_error), and
+//!     conversion to `io::Error` with [`into_io_error_full`].
+//!   - The [last error capture](record_last_error), readable from abort and
+//!     signal handlers.
+//!   - [Reporter backends](ErrorReporter), with the global [`report`] entry
+//!     point, [NDJSON output](NdjsonReporter), [deduplication](DedupReporter),
+//!     [rate limiting](RateLimitedReporter), [sampling](SamplingReporter),
+//!     [background reporting](BackgroundReporter) and [batching](BatchingReporter).
+//!   - [Statistics](ChainStats) of the chains by root cause.
+//!   - Global [format configuration](set_hook), and runtime
+//!     [format selection](ReportFormat) with the `RUST_ERROR_FORMAT`
+//!     environment variable.
+//! - `std-error`: use `std::error::Error` instead of `core::error::Error`. This
+//!   removes the dependency on `error_in_core`, to support Rust versions
+//!   before 1.81.0 (down to 1.70.0) at the cost of `no_std` support.
 //!
 //! The formatting uses `: ` as it follows existing conventions and allows to
 //! keep the formatted error on a single line if the error messages don't
@@ -95,61 +192,528 @@
 //! default const values as of Rust 1.83. See the following Rust issues:
 //! [#27336](https://github.com/rust-lang/rust/issues/27336),
 //! [#85077](https://github.com/rust-lang/rust/issues/85077).
+#![cfg_attr(display_full_error_unstable, feature(error_generic_member_access))]
 #![deny(missing_docs)]
+#![cfg_attr(
+  not(test),
+  deny(
+    clippy::arithmetic_side_effects,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    clippy::panic_in_result_fn,
+    clippy::todo,
+    clippy::unimplemented,
+    clippy::unreachable,
+    clippy::unwrap_used
+  )
+)]
 #![no_std]
 #[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
+#[cfg(all(test, feature = "derive"))]
+extern crate self as display_full_error;
+
+#[cfg(any(feature = "std", feature = "std-error"))]
+extern crate std;
+
+#[cfg(not(feature = "std-error"))]
+use ::core::error::Error;
+#[cfg(feature = "std-error")]
+use ::std::error::Error;
+
+#[cfg(feature = "derive")]
+pub use ::display_full_error_derive::DisplayFullError;
 
 /// Maximum number of messages to print in a single full error.
 ///
 /// This value includes the initial error. If there are more errors left, the
 /// next error will be printed as `...` and formatting will end.
-pub const MESSAGE_LIMIT: u16 = 1024;
+///
+/// The limit is 1024 by default, it can be changed with the `limit-64`,
+/// `limit-256` and `limit-4096` features. If multiple limit features are
+/// enabled, the highest limit is used.
+pub const MESSAGE_LIMIT: u16 = if cfg!(feature = "limit-4096") {
+  4096
+} else if cfg!(feature = "limit-256") {
+  256
+} else if cfg!(feature = "limit-64") {
+  64
+} else {
+  1024
+};
+
+mod adapter;
+mod ascii;
+#[cfg(feature = "std")]
+mod background;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+mod capture;
+mod causes;
+mod chain;
+mod classify;
+#[cfg(feature = "alloc")]
+mod collected;
+pub mod compat;
+mod context;
+#[cfg(feature = "std")]
+mod crash;
+mod csv;
+mod debug;
+#[cfg(feature = "std")]
+mod dedup;
+#[cfg(doctest)]
+mod derive_checks;
+#[cfg(feature = "alloc")]
+mod diff;
+mod dot;
+mod dump;
+mod elided;
+#[cfg(feature = "std")]
+mod env_format;
+mod eq;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod fields;
+mod fingerprint;
+pub mod format_v1;
+mod head;
+#[cfg(feature = "std")]
+mod hook;
+#[cfg(feature = "std")]
+mod io;
+mod json;
+mod logger;
+mod messages;
+#[cfg(feature = "std")]
+mod ndjson;
+mod option;
+mod options;
+#[cfg(feature = "alloc")]
+mod panic;
+mod percent;
+mod postcard;
+pub mod prelude;
+mod problem;
+#[cfg(feature = "std")]
+mod rate_limit;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod reporter;
+mod result;
+#[cfg(feature = "std")]
+mod sampling;
+mod short;
+#[cfg(feature = "std")]
+mod stats;
+mod tail;
+mod tee;
+mod timestamp;
+mod toml;
+mod tree;
+mod validate;
+#[cfg(feature = "std")]
+mod wrap;
+mod xml;
+mod yaml;
+
+pub use adapter::{
+  ChainAdapter, Colorize, DedupMessages, DisplayFullErrorAdapted, Layered, RootFirst, Sanitize, Truncate,
+};
+pub use ascii::DisplayFullErrorAscii;
+#[cfg(feature = "std")]
+pub use background::{BackgroundReporter, ErrorSnapshot};
+#[cfg(feature = "std")]
+pub use batch::BatchingReporter;
+#[cfg(feature = "std")]
+pub use capture::{last_error, record_last_error, set_last_error_capture, LastError, LAST_ERROR_CAPACITY};
+pub use causes::DisplayFullErrorCauses;
+pub use chain::Chain;
+pub use classify::{ClassifyChain, DefaultClassifier, ErrorCategory};
+#[cfg(feature = "alloc")]
+pub use collected::CollectedChain;
+#[cfg(feature = "alloc")]
+pub use context::ChainedError;
+pub use context::ContextError;
+#[cfg(feature = "std")]
+pub use crash::CrashReport;
+pub use csv::DisplayFullErrorCsv;
+#[cfg(feature = "std")]
+pub use dedup::DedupReporter;
+#[cfg(feature = "alloc")]
+pub use diff::ChainDiff;
+pub use dot::DisplayFullErrorDot;
+pub use dump::{CrashDump, DecodeError, DumpMessages};
+pub use elided::DisplayFullErrorElided;
+#[cfg(feature = "std")]
+pub use env_format::{DisplayReportFormat, ReportFormat};
+#[cfg(feature = "ffi")]
+pub use ffi::{display_full_error_format, format_full_into, ErrorHandle, FORMAT_ERROR};
+pub use fields::{fields_of, ChainFields, DisplayFullErrorJsonFields, FieldsLookup};
+pub use head::DisplayFullErrorHead;
+#[cfg(feature = "std")]
+pub use hook::{set_hook, Indent, ReportHook, SetHookError, Severity};
+#[cfg(feature = "std")]
+pub use io::into_io_error_full;
+pub use json::DisplayFullErrorJson;
+pub use logger::format_record_error;
+pub use messages::{IndexedMessages, Message};
+#[cfg(feature = "std")]
+pub use ndjson::NdjsonReporter;
+pub use option::{DisplayFullOption, DisplayFullOptionExt};
+#[cfg(feature = "alloc")]
+pub use options::MessageTransform;
+pub use options::{DisplayFullErrorWith, FormatOptions, LetterCase, SourceFilter, DISPLAY_ERROR, REPEATED_TYPES_LIMIT};
+#[cfg(feature = "alloc")]
+pub use panic::DisplayPanicPayload;
+pub use percent::DisplayFullErrorPercentEncoded;
+pub use postcard::{encode_postcard_into, EncodeError};
+pub use problem::{DisplayFullErrorProblem, PROBLEM_JSON_CONTENT_TYPE};
+#[cfg(feature = "std")]
+pub use rate_limit::{RateLimitedReporter, SuppressedErrors};
+#[cfg(feature = "std")]
+pub use report::Report;
+#[cfg(feature = "std")]
+pub use reporter::{report, set_reporter, ErrorReporter, FnReporter, SetReporterError, StderrReporter, WriteReporter};
+pub use result::{DisplayFullResult, DisplayFullResultExt};
+#[cfg(feature = "std")]
+pub use sampling::{SampledError, SamplingReporter};
+pub use short::DisplayFullErrorShort;
+#[cfg(feature = "std")]
+pub use stats::{ChainStats, ChainStatsSummary, RootCauseCount};
+pub use tail::DisplayFullErrorTail;
+pub use tee::write_tee;
+#[cfg(feature = "std")]
+pub use timestamp::system_clock;
+pub use timestamp::{Clock, DisplayRfc3339};
+pub use toml::DisplayFullErrorToml;
+pub use tree::DisplayFullErrorTree;
+pub use validate::SingleLineError;
+pub use xml::DisplayFullErrorXml;
+pub use yaml::DisplayFullErrorYaml;
 
 /// Formatting wrapper to display errors, including their sources.
 ///
 /// Error messages are formatted on a single line, separated with `: `; up to
-/// 1024 messages per chain are printed, after which a single `: ...` is printed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// [`MESSAGE_LIMIT`] messages per chain are printed, after which a single
+/// `: ...` is printed.
+///
+/// The `Debug` format prints the `Debug` format of the error. The alternate
+/// `Debug` format (`{:#?}`) instead prints each message of the chain as a
+/// string, which is stable and readable in snapshot tests. The wrapper can also be compared with a `str`, which
+/// checks the formatted chain without allocating.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// assert_eq!(
+///   format!("{:#?}", err.display_full()),
+///   "DisplayFullError(\n    \"permission denied\",\n)",
+/// );
+/// assert_eq!(err.display_full(), "permission denied");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DisplayFullError<'e, E>(pub &'e E)
 where
-  E: ::core::error::Error + ?Sized;
+  E: Error + ?Sized;
+
+impl<E> DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  /// Get the number of messages printed by this wrapper, including the error
+  /// itself.
+  ///
+  /// This respects [`MESSAGE_LIMIT`]: the `...` printed for longer chains is
+  /// not counted. The chain is walked, but no message is formatted, so this
+  /// can be used to choose a display style before formatting.
+  ///
+  /// ```rust
+  /// use ::display_full_error::DisplayFullErrorExt;
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// assert_eq!(err.display_full().source_count(), 1);
+  /// ```
+  pub fn source_count(&self) -> usize {
+    chain_len(self.0).0
+  }
+}
+
+impl<E> ::core::fmt::Debug for DisplayFullError<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    if !f.alternate() {
+      return f.debug_tuple("DisplayFullError").field(&self.0).finish();
+    }
+    let mut tuple = f.debug_tuple("DisplayFullError");
+    for (_, message) in IndexedMessages::new(self.0) {
+      tuple.field(&debug::DebugMessage(message));
+    }
+    if chain_len(self.0).1 {
+      tuple.field(&debug::DebugMessage("..."));
+    }
+    tuple.finish()
+  }
+}
 
 impl<E> ::core::fmt::Display for DisplayFullError<'_, E>
 where
-  E: ::core::error::Error + ?Sized,
+  E: Error + ?Sized,
 {
   fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-    core::fmt::Display::fmt(&self.0, f)?;
-    let mut printed: u16 = 1;
-    for e in ::core::iter::successors(self.0.source(), |e| e.source()) {
-      if printed >= MESSAGE_LIMIT {
-        f.write_str(": ...")?;
-        return Ok(());
-      }
-      f.write_str(": ")?;
-      ::core::fmt::Display::fmt(e, f)?;
-      printed = printed.saturating_add(1);
+    ::core::fmt::Display::fmt(&format_v1::DisplayFullError(self.0), f)
+  }
+}
+
+/// Iterate over the sources of an error, excluding the error itself.
+pub(crate) fn sources<'e, E>(error: &'e E) -> impl Iterator<Item = &'e (dyn Error + 'static)>
+where
+  E: Error + ?Sized,
+{
+  ::core::iter::successors(error.source(), |&e| e.source())
+}
+
+/// Source of a chain printed by the structured outputs, see [`printed_sources`].
+#[derive(Clone, Copy)]
+pub(crate) enum PrintedSource<'e> {
+  /// Source printed with its message.
+  Message(&'e (dyn Error + 'static)),
+  /// Marker replacing the sources past [`MESSAGE_LIMIT`], printed as `...`.
+  Ellipsis,
+}
+
+impl ::core::fmt::Display for PrintedSource<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    match self {
+      Self::Message(e) => ::core::fmt::Display::fmt(e, f),
+      Self::Ellipsis => f.write_str("..."),
     }
-    Ok(())
   }
 }
 
+/// Iterate over the sources of an error printed within [`MESSAGE_LIMIT`]
+/// messages, excluding the error itself but counting it towards the limit,
+/// followed by a single [`PrintedSource::Ellipsis`] if the chain is longer.
+pub(crate) fn printed_sources<'e, E>(error: &'e E) -> impl Iterator<Item = PrintedSource<'e>>
+where
+  E: Error + ?Sized,
+{
+  let mut sources = sources(error);
+  let mut printed: u16 = 1;
+  ::core::iter::from_fn(move || {
+    if printed > MESSAGE_LIMIT {
+      return None;
+    }
+    let e = sources.next()?;
+    if printed == MESSAGE_LIMIT {
+      printed = printed.saturating_add(1);
+      return Some(PrintedSource::Ellipsis);
+    }
+    printed = printed.saturating_add(1);
+    Some(PrintedSource::Message(e))
+  })
+}
+
+/// Get the number of messages of the chain within [`MESSAGE_LIMIT`], including
+/// the error itself, and whether the chain exceeds the limit.
+pub(crate) fn chain_len<E>(error: &E) -> (usize, bool)
+where
+  E: Error + ?Sized,
+{
+  let mut sources = sources(error);
+  let len = sources
+    .by_ref()
+    .take(usize::from(MESSAGE_LIMIT.saturating_sub(1)))
+    .count();
+  (len.saturating_add(1), sources.next().is_some())
+}
+
 /// Private module, to implement the trait sealing pattern.
 mod private {
+  use crate::Error;
+
   /// To restrict `DisplayFullErrorExt` implementations to this crate.
   pub trait Sealed {}
+
+  /// To restrict `DisplayFullResultExt` implementations to this crate.
+  pub trait SealedResult {}
+
+  /// To restrict `DisplayFullOptionExt` implementations to this crate.
+  pub trait SealedOption {}
+
+  /// Convert sized errors and error trait objects to `&dyn Error`.
+  ///
+  /// This is required to use the whole chain as `&dyn Error`, including the
+  /// initial error: the extension trait is also implemented for unsized
+  /// errors, which can't be converted directly.
+  pub trait AsDynError {
+    /// Get this error as a trait object.
+    fn as_dyn_error(&self) -> &(dyn Error + 'static);
+  }
+
+  impl<E> AsDynError for E
+  where
+    E: Error + 'static,
+  {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+      self
+    }
+  }
+
+  impl AsDynError for dyn Error + 'static {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+      self
+    }
+  }
+
+  impl AsDynError for dyn Error + Send + 'static {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+      self
+    }
+  }
+
+  impl AsDynError for dyn Error + Send + Sync + 'static {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+      self
+    }
+  }
 }
 
 /// Extension trait providing convenience methods on [errors](::core::error::Error).
 ///
 /// This trait provides a blanket implementation for all types implementing [the standard `Error` trait](::core::error::Error).
-pub trait DisplayFullErrorExt: ::core::error::Error + private::Sealed {
-  /// Get a reference to this error wrapped in a [`DisplayFullError`] formatter, to display the error with all its sources.
+pub trait DisplayFullErrorExt: Error + private::Sealed {
+  /// Get a reference to this error wrapped in a [`DisplayFullError`](struct@DisplayFullError) formatter, to display the error with all its sources.
   fn display_full(&self) -> DisplayFullError<'_, Self> {
     DisplayFullError(self)
   }
 
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorWith`]
+  /// formatter, to display the error with all its sources using custom
+  /// [options](FormatOptions).
+  fn display_full_with<'e>(&'e self, options: FormatOptions<'e>) -> DisplayFullErrorWith<'e, Self> {
+    DisplayFullErrorWith(self, options)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorAdapted`]
+  /// formatter, to display the chain through the given [`ChainAdapter`].
+  fn display_adapted<A>(&self, adapter: A) -> DisplayFullErrorAdapted<'_, Self, A>
+  where
+    A: ChainAdapter,
+  {
+    DisplayFullErrorAdapted(self, adapter)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorAscii`]
+  /// formatter, to display the full chain with non-ASCII chars escaped.
+  fn display_ascii(&self) -> DisplayFullErrorAscii<'_, Self> {
+    DisplayFullErrorAscii(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorCsv`]
+  /// formatter, to display the full chain as a quoted CSV field.
+  fn display_csv(&self) -> DisplayFullErrorCsv<'_, Self> {
+    DisplayFullErrorCsv(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorDot`]
+  /// formatter, to display the chain as a Graphviz DOT graph.
+  fn display_dot(&self) -> DisplayFullErrorDot<'_, Self> {
+    DisplayFullErrorDot(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorElided`]
+  /// formatter, to display only the outermost and deepest `keep` messages of
+  /// the chain.
+  fn display_elided(&self, keep: usize) -> DisplayFullErrorElided<'_, Self> {
+    DisplayFullErrorElided(self, keep)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorHead`]
+  /// formatter, to display only the outermost `count` messages of the chain.
+  fn display_head(&self, count: usize) -> DisplayFullErrorHead<'_, Self> {
+    DisplayFullErrorHead(self, count)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorJson`]
+  /// formatter, to display the chain as a JSON array of strings.
+  fn display_json(&self) -> DisplayFullErrorJson<'_, Self> {
+    DisplayFullErrorJson(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorJsonFields`]
+  /// formatter, to display the chain as a JSON array of objects with the
+  /// [fields](ChainFields) found by `lookup`.
+  fn display_json_fields<'e>(&'e self, lookup: &'e FieldsLookup<'e>) -> DisplayFullErrorJsonFields<'e, Self> {
+    DisplayFullErrorJsonFields(self, lookup)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorPercentEncoded`]
+  /// formatter, to display the full chain percent-encoded for URLs.
+  fn display_percent_encoded(&self) -> DisplayFullErrorPercentEncoded<'_, Self> {
+    DisplayFullErrorPercentEncoded(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorProblem`]
+  /// formatter, to display an RFC 7807 Problem Details JSON object with an
+  /// optional HTTP `status`.
+  fn display_problem(&self, status: Option<u16>) -> DisplayFullErrorProblem<'_, Self> {
+    DisplayFullErrorProblem(self, status)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorShort`]
+  /// formatter, to display only the outermost message and the root cause.
+  fn display_short(&self) -> DisplayFullErrorShort<'_, Self> {
+    DisplayFullErrorShort(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorTail`]
+  /// formatter, to display only the deepest `count` messages of the chain.
+  fn display_tail(&self, count: usize) -> DisplayFullErrorTail<'_, Self> {
+    DisplayFullErrorTail(self, count)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorToml`]
+  /// formatter, to display the chain as a TOML fragment.
+  fn display_toml(&self) -> DisplayFullErrorToml<'_, Self> {
+    DisplayFullErrorToml(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorTree`]
+  /// formatter, to display the chain as a tree with box-drawing connectors.
+  fn display_tree(&self) -> DisplayFullErrorTree<'_, Self> {
+    DisplayFullErrorTree(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorXml`]
+  /// formatter, to display the chain as an XML element.
+  fn display_xml(&self) -> DisplayFullErrorXml<'_, Self> {
+    DisplayFullErrorXml(self)
+  }
+
+  /// Get a reference to this error wrapped in a [`DisplayFullErrorYaml`]
+  /// formatter, to display the chain as a YAML block sequence.
+  fn display_yaml(&self) -> DisplayFullErrorYaml<'_, Self> {
+    DisplayFullErrorYaml(self)
+  }
+
+  /// Get the message of this error and its sources as two separate formatters.
+  ///
+  /// The first formatter prints only the message of this error, the second
+  /// prints the joined sources (see [`DisplayFullErrorCauses`]). This matches
+  /// structured log schemas with distinct `error` and `error.cause` fields.
+  fn display_split(&self) -> (&Self, DisplayFullErrorCauses<'_, Self>) {
+    (self, DisplayFullErrorCauses(self))
+  }
+
   /// Shorthand for `.display_full().to_string()`
   ///
   /// Requires the `alloc` feature.
@@ -159,11 +723,218 @@ pub trait DisplayFullErrorExt: ::core::error::Error + private::Sealed {
 
     self.display_full().to_string()
   }
+
+  /// Same as [`to_string_full`](Self::to_string_full), but formats the chain
+  /// in a reusable thread-local buffer.
+  ///
+  /// The returned string is a copy of the buffer with an exact capacity, so
+  /// each call performs a single allocation instead of growing a new string
+  /// while formatting. This is intended for hot paths logging many chains.
+  ///
+  /// Requires the `std` feature.
+  #[cfg(feature = "std")]
+  fn to_string_full_cached(&self) -> alloc::string::String {
+    cache::to_string_full_cached(self)
+  }
+
+  /// Get the message of this error and its joined sources as two strings.
+  ///
+  /// See [`display_split`](Self::display_split).
+  ///
+  /// Requires the `alloc` feature.
+  #[cfg(feature = "alloc")]
+  fn to_string_split(&self) -> (alloc::string::String, alloc::string::String) {
+    use crate::alloc::string::ToString;
+
+    let (message, causes) = self.display_split();
+    (message.to_string(), causes.to_string())
+  }
+
+  /// Compare the chain of this error with the chain of `other`.
+  ///
+  /// Returns the first difference, or `None` if the messages of both chains
+  /// are equal. See [`ChainDiff`].
+  ///
+  /// Requires the `alloc` feature.
+  #[cfg(feature = "alloc")]
+  fn diff_chain<O>(&self, other: &O) -> Option<ChainDiff>
+  where
+    O: Error + ?Sized,
+  {
+    diff::diff_chains(self, other)
+  }
+
+  /// Get the full error chain as a NUL-terminated [`CString`](alloc::ffi::CString).
+  ///
+  /// Interior NUL bytes are replaced with `U+FFFD REPLACEMENT CHARACTER`, so
+  /// the message is never truncated when read by C APIs such as `syslog(3)`.
+  ///
+  /// Requires the `alloc` feature.
+  #[cfg(feature = "alloc")]
+  fn to_cstring_full(&self) -> alloc::ffi::CString {
+    let mut message = self.to_string_full();
+    if message.contains('\0') {
+      message = message.replace('\0', "\u{FFFD}");
+    }
+    alloc::ffi::CString::new(message).unwrap_or_default()
+  }
+
+  /// Iterate over the messages of the chain, with their index.
+  ///
+  /// See [`IndexedMessages`].
+  fn indexed_messages(&self) -> IndexedMessages<'_, Self> {
+    IndexedMessages::new(self)
+  }
+
+  /// Iterate over the errors of the chain, starting with this error.
+  ///
+  /// See [`Chain`].
+  fn chain(&self) -> Chain<'_>
+  where
+    Self: private::AsDynError,
+  {
+    Chain::new(self.as_dyn_error())
+  }
+
+  /// Collect the errors of the chain, starting with this error.
+  ///
+  /// See [`CollectedChain`].
+  ///
+  /// Requires the `alloc` feature.
+  #[cfg(feature = "alloc")]
+  fn collect_chain(&self) -> CollectedChain<'_>
+  where
+    Self: private::AsDynError,
+  {
+    CollectedChain::new(self.as_dyn_error())
+  }
+
+  /// Write the full chain to an [`io::Write`](std::io::Write) output.
+  ///
+  /// Requires the `std` feature.
+  #[cfg(feature = "std")]
+  fn write_full_to<W>(&self, mut writer: W) -> std::io::Result<()>
+  where
+    W: std::io::Write,
+  {
+    write!(writer, "{}", self.display_full())
+  }
+
+  /// Print the full chain to stderr, followed by a newline.
+  ///
+  /// The format can be customized with the global [`ReportHook`], or at
+  /// runtime with the `RUST_ERROR_FORMAT` environment variable (see
+  /// [`ReportFormat`]). Unlike `eprintln!`, this
+  /// never panics: failures to write to stderr are ignored.
+  ///
+  /// Requires the `std` feature.
+  #[cfg(feature = "std")]
+  fn eprint_full(&self) {
+    use std::io::Write;
+
+    let format = hook::resolve_format(ReportFormat::Full);
+    let _ = writeln!(std::io::stderr().lock(), "{}", hook::DisplayHooked(self, format));
+  }
+
+  /// Check if the chain contains an [`io::Error`](std::io::Error) of the provided kind.
+  ///
+  /// This is useful for retry logic, e.g. to check for
+  /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) or
+  /// [`NotFound`](std::io::ErrorKind::NotFound) errors.
+  ///
+  /// Requires the `std` feature.
+  #[cfg(feature = "std")]
+  fn has_io_kind(&self, kind: std::io::ErrorKind) -> bool
+  where
+    Self: private::AsDynError,
+  {
+    self
+      .chain()
+      .any(|e| e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == kind))
+  }
+
+  /// Get the first OS error code found in the chain.
+  ///
+  /// This returns the [`raw_os_error`](std::io::Error::raw_os_error) of the
+  /// first [`io::Error`](std::io::Error) of the chain which has one. This is
+  /// useful to map failures back to POSIX error codes, e.g. for exit statuses.
+  ///
+  /// Requires the `std` feature.
+  #[cfg(feature = "std")]
+  fn find_raw_os_error(&self) -> Option<i32>
+  where
+    Self: private::AsDynError,
+  {
+    self.chain().find_map(|e| {
+      e.downcast_ref::<std::io::Error>()
+        .and_then(std::io::Error::raw_os_error)
+    })
+  }
+
+  /// Check that every message of the chain fits on a single line.
+  ///
+  /// Returns an error with the index of the first message containing a
+  /// newline or another control character. This is intended for tests, to
+  /// enforce that your own error types keep the formatted chain on a single
+  /// line.
+  fn validate_single_line(&self) -> Result<(), SingleLineError> {
+    validate::validate_single_line(self)
+  }
+
+  /// Encode the chain into `buf` with a compact binary format, returning the
+  /// number of bytes written.
+  ///
+  /// The dump is decoded with [`CrashDump::decode`]. This is intended to
+  /// store the last error in an EEPROM or a backup register, and decode it
+  /// after a reboot.
+  fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    dump::encode_into(self, buf)
+  }
+
+  /// Get a fingerprint identifying the full chain.
+  ///
+  /// This is the 64-bit FNV-1a hash of the chain formatted by
+  /// [`DisplayFullError`](struct@DisplayFullError). It is stable across runs
+  /// and platforms, so it can be used to group or deduplicate identical
+  /// errors.
+  fn fingerprint(&self) -> u64 {
+    fingerprint::fingerprint(self)
+  }
+
+  /// Get the source at the provided depth in the chain of sources.
+  ///
+  /// `nth_source(0)` is the direct [source](::core::error::Error::source) of
+  /// this error, `nth_source(1)` is the source of the source, etc. Returns
+  /// `None` if the chain is shorter.
+  fn nth_source(&self, n: usize) -> Option<&(dyn Error + 'static)> {
+    sources(self).nth(n)
+  }
+
+  /// Get the deepest source that would be printed by [`DisplayFullError`](struct@DisplayFullError).
+  ///
+  /// This is the last source of the chain, unless the chain exceeds
+  /// [`MESSAGE_LIMIT`]: in this case this is the last source before the limit.
+  /// Returns `None` if this error has no source.
+  fn deepest_source(&self) -> Option<&(dyn Error + 'static)> {
+    sources(self).take(usize::from(MESSAGE_LIMIT.saturating_sub(1))).last()
+  }
 }
 
-impl<E> private::Sealed for E where E: ::core::error::Error + ?Sized {}
+/// Support items for the code generated by the derive macro, not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+  #[cfg(not(feature = "std-error"))]
+  pub use ::core::error::Error;
+  #[cfg(feature = "std-error")]
+  pub use ::std::error::Error;
+
+  pub use crate::private::AsDynError;
+}
 
-impl<E> DisplayFullErrorExt for E where E: ::core::error::Error + ?Sized {}
+impl<E> private::Sealed for E where E: Error + ?Sized {}
+
+impl<E> DisplayFullErrorExt for E where E: Error + ?Sized {}
 
 #[cfg(test)]
 mod tests {
@@ -173,7 +944,7 @@ mod tests {
   use ::core::{error, fmt};
 
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-  enum UploadError {
+  pub(crate) enum UploadError {
     Permission(PermissionError),
     #[allow(dead_code)]
     Limit(LimitError),
@@ -195,7 +966,7 @@ mod tests {
   }
 
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-  struct PermissionError;
+  pub(crate) struct PermissionError;
 
   impl fmt::Display for PermissionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -206,7 +977,7 @@ mod tests {
   impl error::Error for PermissionError {}
 
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-  struct LimitError;
+  pub(crate) struct LimitError;
 
   impl fmt::Display for LimitError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -216,6 +987,77 @@ mod tests {
 
   impl error::Error for LimitError {}
 
+  /// Error with an arbitrary chain of messages
+  #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub(crate) struct MessageError {
+    message: &'static str,
+    source: Option<::alloc::boxed::Box<MessageError>>,
+  }
+
+  impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      fmt::Display::fmt(self.message, f)
+    }
+  }
+
+  impl error::Error for MessageError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+      self.source.as_deref().map(|e| e as &(dyn error::Error + 'static))
+    }
+  }
+
+  /// Error providing a `&str` tag with the generic member access API, to
+  /// test that wrapping errors forward `provide`.
+  #[cfg(display_full_error_unstable)]
+  #[derive(Debug)]
+  pub(crate) struct QueryError;
+
+  #[cfg(display_full_error_unstable)]
+  impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.write_str("query failed")
+    }
+  }
+
+  #[cfg(display_full_error_unstable)]
+  impl error::Error for QueryError {
+    fn provide<'a>(&'a self, request: &mut error::Request<'a>) {
+      request.provide_ref::<str>("SELECT 1");
+    }
+  }
+
+  /// Error which is its own source, to test chains exceeding `MESSAGE_LIMIT`.
+  #[derive(Debug)]
+  pub(crate) struct CyclicError;
+
+  impl fmt::Display for CyclicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.write_str("cycle detected")
+    }
+  }
+
+  impl error::Error for CyclicError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+      Some(self as &dyn error::Error)
+    }
+  }
+
+  /// Build an error chain with the provided messages, from the outermost to the deepest one.
+  pub(crate) fn message_chain(messages: &[&'static str]) -> MessageError {
+    let (last, rest) = messages.split_last().expect("chain is not empty");
+    let mut error = MessageError {
+      message: last,
+      source: None,
+    };
+    for message in rest.iter().rev() {
+      error = MessageError {
+        message,
+        source: Some(::alloc::boxed::Box::new(error)),
+      };
+    }
+    error
+  }
+
   #[test]
   fn error_without_source() {
     let input = PermissionError;
@@ -232,26 +1074,194 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn debug_prints_error() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = format!("{:?}", input.display_full());
+    assert_eq!(actual, format!("DisplayFullError({input:?})"));
+  }
+
+  #[test]
+  fn debug_lists_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = format!("{:#?}", input.display_full());
+    let expected = String::from("DisplayFullError(\n    \"upload failed\",\n    \"permission denied\",\n)");
+    assert_eq!(actual, expected);
+  }
+
   #[test]
   fn error_with_cyclic_source_chain() {
+    let input = CyclicError;
+    let actual: String = input.display_full().to_string();
+    let expected = format!(
+      "{}...",
+      ::alloc::vec!["cycle detected: "; usize::from(MESSAGE_LIMIT)].join("")
+    );
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn printed_sources_end_with_ellipsis() {
+    let input = CyclicError;
+    let mut sources = printed_sources(&input);
+    let messages = sources
+      .by_ref()
+      .take_while(|source| matches!(source, PrintedSource::Message(_)))
+      .count();
+    assert_eq!(messages, usize::from(MESSAGE_LIMIT - 1));
+    assert!(sources.next().is_none());
+    let last = printed_sources(&input).last().map(|source| source.to_string());
+    assert_eq!(last, Some(String::from("...")));
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual: String = printed_sources(&input).map(|source| source.to_string()).collect();
+    assert_eq!(actual, String::from("permission denied"));
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn to_cstring_full_replaces_nul() {
+    let input = message_chain(&["upload failed", "invalid name \"a\0b\""]);
+    let actual = input.to_cstring_full();
+    assert_eq!(actual.to_str(), Ok("upload failed: invalid name \"a\u{FFFD}b\""));
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn has_io_kind() {
     #[derive(Debug)]
-    struct CyclicError;
+    struct ReadError(::std::io::Error);
 
-    impl fmt::Display for CyclicError {
+    impl fmt::Display for ReadError {
       fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("cycle detected")
+        f.write_str("read failed")
       }
     }
 
-    impl error::Error for CyclicError {
+    impl error::Error for ReadError {
       fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        Some(self as &dyn error::Error)
+        Some(&self.0)
       }
     }
 
-    let input = CyclicError;
-    let actual: String = input.display_full().to_string();
-    let expected = format!("{}...", ["cycle detected: "; 1024].join(""));
-    assert_eq!(actual, expected);
+    let input: ::alloc::boxed::Box<dyn error::Error> =
+      ::alloc::boxed::Box::new(ReadError(::std::io::ErrorKind::NotFound.into()));
+    assert!(input.has_io_kind(::std::io::ErrorKind::NotFound));
+    assert!(!input.has_io_kind(::std::io::ErrorKind::WouldBlock));
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn find_raw_os_error() {
+    let input: ::alloc::boxed::Box<dyn error::Error> = ::alloc::boxed::Box::new(::std::io::Error::from_raw_os_error(2));
+    assert_eq!(input.find_raw_os_error(), Some(2));
+    assert_eq!(PermissionError.find_raw_os_error(), None);
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn write_full_to() {
+    let mut output: ::alloc::vec::Vec<u8> = ::alloc::vec::Vec::new();
+    let result = UploadError::Permission(PermissionError).write_full_to(&mut output);
+    assert!(result.is_ok());
+    assert_eq!(output, b"upload failed: permission denied");
+  }
+
+  #[test]
+  fn source_count() {
+    let input = UploadError::Permission(PermissionError);
+    assert_eq!(input.display_full().source_count(), 2);
+    assert_eq!(PermissionError.display_full().source_count(), 1);
+  }
+
+  #[test]
+  fn nth_source() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: Option<String> = input.nth_source(0).map(|e| e.to_string());
+    assert_eq!(actual, Some(String::from("permission denied")));
+    assert!(input.nth_source(1).is_none());
+  }
+
+  #[test]
+  fn deepest_source() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: Option<String> = input.deepest_source().map(|e| e.to_string());
+    assert_eq!(actual, Some(String::from("permission denied")));
+    assert!(PermissionError.deepest_source().is_none());
+  }
+
+  #[cfg(feature = "derive")]
+  mod derive {
+    use super::*;
+    use ::alloc::boxed::Box;
+
+    #[derive(Debug, DisplayFullError)]
+    enum UploadError {
+      #[display("upload failed")]
+      Permission(#[source] PermissionError),
+      #[display("upload of {size} bytes failed")]
+      Limit { size: u64, source: LimitError },
+      #[display("upload failed: {_0}")]
+      Other(&'static str),
+    }
+
+    #[derive(Debug, DisplayFullError)]
+    #[display("permission denied")]
+    struct PermissionError;
+
+    #[derive(Debug, DisplayFullError)]
+    #[display("upload exceeds max limit")]
+    struct LimitError;
+
+    #[derive(Debug, DisplayFullError)]
+    #[display("request {id} failed")]
+    struct RequestError {
+      id: u32,
+      #[source]
+      cause: Option<Box<dyn error::Error + Send + Sync>>,
+    }
+
+    #[test]
+    fn derive_tuple_variant_source() {
+      let input = UploadError::Permission(PermissionError);
+      let actual: String = input.display_full().to_string();
+      let expected = String::from("upload failed: permission denied");
+      assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn derive_named_variant_source() {
+      let input = UploadError::Limit {
+        size: 100,
+        source: LimitError,
+      };
+      let actual: String = input.display_full().to_string();
+      let expected = String::from("upload of 100 bytes failed: upload exceeds max limit");
+      assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn derive_variant_without_source() {
+      let input = UploadError::Other("disk full");
+      assert!(error::Error::source(&input).is_none());
+      let actual: String = input.display_full().to_string();
+      let expected = String::from("upload failed: disk full");
+      assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn derive_optional_boxed_source() {
+      let input = RequestError {
+        id: 1,
+        cause: Some(Box::new(UploadError::Permission(PermissionError))),
+      };
+      let actual: String = input.display_full().to_string();
+      let expected = String::from("request 1 failed: upload failed: permission denied");
+      assert_eq!(actual, expected);
+
+      let input = RequestError { id: 2, cause: None };
+      let actual: String = input.display_full().to_string();
+      let expected = String::from("request 2 failed");
+      assert_eq!(actual, expected);
+    }
   }
 }
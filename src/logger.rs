@@ -0,0 +1,41 @@
+//! Helper for the custom format callbacks of logging libraries.
+
+use crate::{DisplayFullError, Error};
+use ::core::fmt;
+
+/// Write the full chain of `error` to `out`.
+///
+/// This is intended to be used in the custom format closures of logging
+/// libraries such as `env_logger`, `fern` or `flexi_logger`, giving them
+/// one-line access to the full chain. The output is the same as
+/// [`DisplayFullError`](struct@DisplayFullError).
+///
+/// ```rust
+/// use ::display_full_error::format_record_error;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// let mut line = String::from("request failed: ");
+/// format_record_error(&err, &mut line).expect("writing to a string never fails");
+/// assert_eq!(line, "request failed: permission denied");
+/// ```
+pub fn format_record_error<W>(error: &(dyn Error + 'static), out: &mut W) -> fmt::Result
+where
+  W: fmt::Write + ?Sized,
+{
+  write!(out, "{}", DisplayFullError(error))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::format_record_error;
+  use crate::tests::message_chain;
+  use ::alloc::string::String;
+
+  #[test]
+  fn write_after_prefix() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let mut actual = String::from("[ERROR] ");
+    assert_eq!(format_record_error(&input, &mut actual), Ok(()));
+    assert_eq!(actual, "[ERROR] upload failed: permission denied");
+  }
+}
@@ -0,0 +1,118 @@
+//! Iteration over the messages of an error chain.
+
+use crate::{Error, MESSAGE_LIMIT};
+use ::core::fmt;
+use ::core::iter::FusedIterator;
+
+/// Iterator over the messages of an error chain, with their index.
+///
+/// The item at index `0` is the error itself, the item at index `1` its
+/// source, etc. Up to [`MESSAGE_LIMIT`] messages are yielded, matching the
+/// messages printed by [`DisplayFullError`](struct@crate::DisplayFullError).
+///
+/// Nothing is formatted or allocated until the messages are displayed, so this
+/// can be used to emit one structured logging field per message (`cause.0`,
+/// `cause.1`, etc.).
+#[derive(Debug, Clone, Copy)]
+pub struct IndexedMessages<'e, E>
+where
+  E: Error + ?Sized,
+{
+  error: &'e E,
+  source: Option<&'e (dyn Error + 'static)>,
+  index: usize,
+}
+
+impl<'e, E> IndexedMessages<'e, E>
+where
+  E: Error + ?Sized,
+{
+  /// Create an iterator over the messages of the chain starting at `error`.
+  pub fn new(error: &'e E) -> Self {
+    Self {
+      error,
+      source: error.source(),
+      index: 0,
+    }
+  }
+}
+
+impl<'e, E> Iterator for IndexedMessages<'e, E>
+where
+  E: Error + ?Sized,
+{
+  type Item = (usize, Message<'e, E>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.index >= usize::from(MESSAGE_LIMIT) {
+      return None;
+    }
+    let message = if self.index == 0 {
+      Message::Error(self.error)
+    } else {
+      let source = self.source?;
+      self.source = source.source();
+      Message::Source(source)
+    };
+    let index = self.index;
+    self.index = self.index.saturating_add(1);
+    Some((index, message))
+  }
+}
+
+impl<E> FusedIterator for IndexedMessages<'_, E> where E: Error + ?Sized {}
+
+/// Single message of an error chain, displaying only this error without its
+/// sources.
+#[derive(Debug, Clone, Copy)]
+pub enum Message<'e, E>
+where
+  E: Error + ?Sized,
+{
+  /// The outermost error of the chain
+  Error(&'e E),
+  /// A source of the outermost error
+  Source(&'e (dyn Error + 'static)),
+}
+
+impl<E> fmt::Display for Message<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Message::Error(e) => fmt::Display::fmt(e, f),
+      Message::Source(e) => fmt::Display::fmt(e, f),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+  use ::alloc::vec::Vec;
+
+  #[test]
+  fn indexed_messages_of_chain() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let actual: Vec<(usize, String)> = input.indexed_messages().map(|(i, m)| (i, m.to_string())).collect();
+    let expected = [
+      (0, String::from("upload failed")),
+      (1, String::from("connection reset")),
+      (2, String::from("permission denied")),
+    ];
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn indexed_messages_without_source() {
+    let actual: Vec<(usize, String)> = PermissionError
+      .indexed_messages()
+      .map(|(i, m)| (i, m.to_string()))
+      .collect();
+    let expected = [(0, String::from("permission denied"))];
+    assert_eq!(actual, expected);
+  }
+}
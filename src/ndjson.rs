@@ -0,0 +1,248 @@
+//! NDJSON reporter backend, writing one JSON object per error.
+
+use crate::fields::write_json_fields;
+use crate::fingerprint::fingerprint;
+use crate::hook::severity_at;
+use crate::json::JsonEscape;
+use crate::{
+  chain_len, sources, system_clock, Chain, ChainFields, Clock, DisplayFullErrorJson, DisplayRfc3339, Error,
+  ErrorReporter, FieldsLookup, MESSAGE_LIMIT,
+};
+use ::core::fmt::{self, Write as _};
+use ::core::time::Duration;
+use ::std::fs::{File, OpenOptions};
+use ::std::io::{self, Write};
+use ::std::path::Path;
+use ::std::sync::{Mutex, PoisonError};
+
+/// Reporter writing each error as a JSON object on its own line
+/// ([NDJSON](https://github.com/ndjson/ndjson-spec)).
+///
+/// Each line is an object with the following members:
+/// - `timestamp`: the time of the report, as an RFC 3339 UTC date with
+///   milliseconds;
+/// - `fingerprint`: the [fingerprint](crate::DisplayFullErrorExt::fingerprint)
+///   of the chain, as 16 hexadecimal digits;
+/// - `messages`: the messages of the chain, as
+///   [`DisplayFullErrorJson`](struct@DisplayFullErrorJson);
+/// - `severities`: the [severity](crate::ReportHook::severity) of each
+///   message, as assigned by the global hook;
+/// - `fields`: only if a [lookup](Self::fields) is set, the
+///   [fields](crate::ChainFields) of each error as an array of objects, with
+///   an empty object for the `...` of truncated chains;
+/// - `root_cause`: the message of the deepest source, or of the error itself
+///   if it has no source.
+///
+/// The output can be ingested directly by tools such as `jq`, Vector or Loki.
+/// Write failures are ignored. The time is read from the [`system_clock`] by
+/// default, see [`clock`](Self::clock).
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{ErrorReporter, NdjsonReporter};
+///
+/// let reporter = NdjsonReporter::new(Vec::new());
+/// reporter.report(&::std::io::Error::other("permission denied"));
+/// let output = String::from_utf8(reporter.into_inner()).expect("output is UTF-8");
+/// assert!(output.ends_with(r#""messages":["permission denied"],"severities":["error"],"root_cause":"permission denied"}
+/// "#));
+/// ```
+#[derive(Debug)]
+pub struct NdjsonReporter<W> {
+  writer: Mutex<W>,
+  clock: Clock,
+  fields: Option<FieldsFn>,
+}
+
+/// Function finding the [`ChainFields`] implementation of an error, see
+/// [`NdjsonReporter::fields`].
+type FieldsFn = for<'e> fn(&'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields>;
+
+impl<W> NdjsonReporter<W>
+where
+  W: Write + Send,
+{
+  /// Create a new reporter writing to `writer`.
+  pub fn new(writer: W) -> Self {
+    Self {
+      writer: Mutex::new(writer),
+      clock: system_clock,
+      fields: None,
+    }
+  }
+
+  /// Read the time of the reports from `clock`.
+  #[must_use]
+  pub fn clock(mut self, clock: Clock) -> Self {
+    self.clock = clock;
+    self
+  }
+
+  /// Include the [fields](crate::ChainFields) found by `lookup` for each
+  /// error of the chain, in a `fields` member.
+  ///
+  /// The lookup is a function, as a [`FieldsLookup`] closure is not
+  /// necessarily `Sync`; it is usually [`fields_of`](crate::fields_of).
+  ///
+  /// ```rust
+  /// use ::display_full_error::{fields_of, ChainFields, ErrorReporter, NdjsonReporter};
+  /// use ::std::fmt;
+  ///
+  /// #[derive(Debug)]
+  /// struct UploadError;
+  ///
+  /// impl fmt::Display for UploadError {
+  ///   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  ///     f.write_str("upload failed")
+  ///   }
+  /// }
+  ///
+  /// impl ::std::error::Error for UploadError {}
+  ///
+  /// impl ChainFields for UploadError {
+  ///   fn fields(&self, visit: &mut dyn FnMut(&str, &dyn fmt::Display) -> fmt::Result) -> fmt::Result {
+  ///     visit("bucket", &"photos")
+  ///   }
+  /// }
+  ///
+  /// let reporter = NdjsonReporter::new(Vec::new()).fields(fields_of::<UploadError>);
+  /// reporter.report(&UploadError);
+  /// let output = String::from_utf8(reporter.into_inner()).expect("output is UTF-8");
+  /// assert!(output.contains(r#""fields":[{"bucket":"photos"}]"#));
+  /// ```
+  #[must_use]
+  pub fn fields(mut self, lookup: for<'e> fn(&'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields>) -> Self {
+    self.fields = Some(lookup);
+    self
+  }
+
+  /// Unwrap this reporter, returning the output.
+  pub fn into_inner(self) -> W {
+    self.writer.into_inner().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl NdjsonReporter<File> {
+  /// Create a new reporter appending to the file at `path`, creating it if
+  /// needed.
+  pub fn open(path: &Path) -> io::Result<Self> {
+    OpenOptions::new().append(true).create(true).open(path).map(Self::new)
+  }
+}
+
+impl<W> ErrorReporter for NdjsonReporter<W>
+where
+  W: Write + Send,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.report_batch(&[error]);
+  }
+
+  fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+    let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+    for &error in errors {
+      let event = ErrorEvent {
+        error,
+        timestamp: (self.clock)(),
+        fields: self.fields.as_ref().map(|lookup| lookup as &FieldsLookup<'_>),
+      };
+      let _ = writeln!(writer, "{event}");
+    }
+    let _ = writer.flush();
+  }
+}
+
+/// JSON object describing a reported error.
+struct ErrorEvent<'e> {
+  error: &'e (dyn Error + 'static),
+  /// Time of the report, since the Unix epoch
+  timestamp: Duration,
+  /// Lookup of the fields of each error, if enabled
+  fields: Option<&'e FieldsLookup<'e>>,
+}
+
+impl fmt::Display for ErrorEvent<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{{\"timestamp\":\"{}\",\"fingerprint\":\"{:016x}\",\"messages\":{}",
+      DisplayRfc3339(self.timestamp),
+      fingerprint(self.error),
+      DisplayFullErrorJson(self.error)
+    )?;
+    let (len, truncated) = chain_len(self.error);
+    let count = if truncated { len.saturating_add(1) } else { len };
+    f.write_str(",\"severities\":[")?;
+    for depth in 0..count {
+      if depth > 0 {
+        f.write_char(',')?;
+      }
+      write!(f, "\"{}\"", severity_at(depth))?;
+    }
+    f.write_char(']')?;
+    if let Some(lookup) = self.fields {
+      f.write_str(",\"fields\":[")?;
+      for (i, e) in Chain::new(self.error).enumerate() {
+        if i > 0 {
+          f.write_char(',')?;
+        }
+        f.write_char('{')?;
+        write_json_fields(f, lookup, e, false)?;
+        f.write_char('}')?;
+      }
+      if truncated {
+        f.write_str(",{}")?;
+      }
+      f.write_char(']')?;
+    }
+    f.write_str(",\"root_cause\":\"")?;
+    let root = sources(self.error)
+      .take(usize::from(MESSAGE_LIMIT.saturating_sub(1)))
+      .last()
+      .unwrap_or(self.error);
+    write!(JsonEscape(&mut *f), "{root}")?;
+    f.write_str("\"}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::NdjsonReporter;
+  use crate::tests::{message_chain, MessageError};
+  use crate::{fields_of, ChainFields, Error, ErrorReporter};
+  use ::alloc::string::String;
+  use ::alloc::vec::Vec;
+  use ::core::time::Duration;
+
+  #[test]
+  fn ndjson_lines() {
+    let reporter = NdjsonReporter::new(Vec::new()).clock(|| Duration::from_millis(1_709_210_096_789));
+    reporter.report(&message_chain(&["upload failed", "permission \"denied\""]));
+    reporter.report(&message_chain(&["job failed"]));
+    let output = String::from_utf8(reporter.into_inner()).unwrap_or_default();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let first = lines.first().copied().unwrap_or_default();
+    assert!(first.starts_with(r#"{"timestamp":"2024-02-29T12:34:56.789Z","fingerprint":""#));
+    assert!(first
+      .ends_with(r#"","messages":["upload failed","permission \"denied\""],"severities":["error","info"],"root_cause":"permission \"denied\""}"#));
+    let second = lines.get(1).copied().unwrap_or_default();
+    assert!(second.starts_with(r#"{"timestamp":"2024-02-29T12:34:56.789Z","#));
+    assert!(second.ends_with(r#""messages":["job failed"],"severities":["error"],"root_cause":"job failed"}"#));
+  }
+
+  #[test]
+  fn ndjson_fields() {
+    fn only_root<'e>(e: &'e (dyn Error + 'static)) -> Option<&'e dyn ChainFields> {
+      fields_of::<MessageError>(e).filter(|_| e.source().is_none())
+    }
+    let reporter = NdjsonReporter::new(Vec::new()).fields(only_root);
+    reporter.report(&message_chain(&["upload failed", "denied"]));
+    let output = String::from_utf8(reporter.into_inner()).unwrap_or_default();
+    assert!(output.ends_with(
+      r#""severities":["error","info"],"fields":[{},{"len":"6","quote\"":"a\"b"}],"root_cause":"denied"}
+"#
+    ));
+  }
+}
@@ -0,0 +1,113 @@
+//! Display wrapper for optional errors, such as the return value of `source()`.
+
+use crate::{private, DisplayFullError, Error};
+use ::core::fmt;
+
+/// Formatting wrapper to display an optional error, printing the full error
+/// chain if there is one.
+///
+/// `Some` values are displayed as with [`DisplayFullError`]. `None` is
+/// displayed as a placeholder, which is empty by default. This avoids `match`
+/// arms at call sites holding optional causes, such as the return value of
+/// [`Error::source`](::core::error::Error::source).
+#[derive(Debug)]
+pub struct DisplayFullOption<'e, E>
+where
+  E: Error + ?Sized,
+{
+  error: Option<&'e E>,
+  placeholder: &'e str,
+}
+
+// Manual implementations, to avoid requiring `E: Clone`
+impl<E> Clone for DisplayFullOption<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<E> Copy for DisplayFullOption<'_, E> where E: Error + ?Sized {}
+
+impl<'e, E> DisplayFullOption<'e, E>
+where
+  E: Error + ?Sized,
+{
+  /// Display `None` as the empty string.
+  pub fn new(error: Option<&'e E>) -> Self {
+    Self::with_placeholder(error, "")
+  }
+
+  /// Display `None` as the provided placeholder.
+  pub fn with_placeholder(error: Option<&'e E>, placeholder: &'e str) -> Self {
+    Self { error, placeholder }
+  }
+}
+
+impl<E> fmt::Display for DisplayFullOption<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.error {
+      Some(e) => fmt::Display::fmt(&DisplayFullError(e), f),
+      None => f.write_str(self.placeholder),
+    }
+  }
+}
+
+/// Extension trait providing convenience methods on optional
+/// [errors](::core::error::Error).
+///
+/// This trait provides a blanket implementation for `Option<&E>`, where `E`
+/// implements [the standard `Error` trait](::core::error::Error).
+pub trait DisplayFullOptionExt<'e, E>: private::SealedOption
+where
+  E: Error + ?Sized,
+{
+  /// Get this optional error wrapped in a [`DisplayFullOption`] formatter,
+  /// displaying `None` as the empty string.
+  fn display_full(&self) -> DisplayFullOption<'e, E>;
+
+  /// Get this optional error wrapped in a [`DisplayFullOption`] formatter,
+  /// displaying `None` as the provided placeholder.
+  fn display_full_or(&self, placeholder: &'e str) -> DisplayFullOption<'e, E>;
+}
+
+impl<E> private::SealedOption for Option<&E> where E: Error + ?Sized {}
+
+impl<'e, E> DisplayFullOptionExt<'e, E> for Option<&'e E>
+where
+  E: Error + ?Sized,
+{
+  fn display_full(&self) -> DisplayFullOption<'e, E> {
+    DisplayFullOption::new(*self)
+  }
+
+  fn display_full_or(&self, placeholder: &'e str) -> DisplayFullOption<'e, E> {
+    DisplayFullOption::with_placeholder(*self, placeholder)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{message_chain, PermissionError};
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn display_some_source() {
+    let input = message_chain(&["job failed", "upload failed", "permission denied"]);
+    let actual: String = input.source().display_full_or("none").to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+  }
+
+  #[test]
+  fn display_none_source() {
+    let input = PermissionError;
+    assert_eq!(input.source().display_full().to_string(), String::new());
+    assert_eq!(input.source().display_full_or("none").to_string(), String::from("none"));
+  }
+}
@@ -0,0 +1,1474 @@
+//! Configurable formatting, with opt-in normalization of the messages.
+
+use crate::{sources, Error, MESSAGE_LIMIT};
+#[cfg(feature = "alloc")]
+use ::alloc::borrow::Cow;
+use ::core::fmt::{self, Write};
+
+/// Options for the [`DisplayFullErrorWith`] formatter.
+///
+/// The default options produce the same output as
+/// [`DisplayFullError`](struct@crate::DisplayFullError), including the
+/// formatter flags (e.g. width or precision) applied to each message. Each
+/// option is opt-in and enabled with the corresponding builder method. The
+/// options rewriting the text of the messages ignore the formatter flags, see
+/// [`per_message_precision`](Self::per_message_precision).
+///
+/// ```rust
+/// use ::display_full_error::{DisplayFullErrorExt, FormatOptions, LetterCase};
+///
+/// const OPTIONS: FormatOptions<'static> = FormatOptions::new()
+///   .source_first_letter(LetterCase::Lower)
+///   .strip_trailing_periods(true);
+///
+/// let err = ::std::io::Error::other("Permission denied.");
+/// assert_eq!(err.display_full_with(OPTIONS).to_string(), "Permission denied");
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct FormatOptions<'a> {
+  source_first_letter: Option<LetterCase>,
+  strip_trailing_periods: bool,
+  trim_whitespace: bool,
+  strip_error_prefix: bool,
+  per_message_precision: bool,
+  grapheme_truncation: bool,
+  max_chars: &'a [usize],
+  debug_messages: bool,
+  resilient: bool,
+  #[cfg(feature = "alloc")]
+  transform: Option<&'a MessageTransform<'a>>,
+  #[cfg(feature = "alloc")]
+  redact: &'a [&'a str],
+  #[cfg(feature = "alloc")]
+  detect_double_print: bool,
+  filter: Option<&'a SourceFilter<'a>>,
+  stop: Option<StopAt<'a>>,
+  repeated: &'a [&'a SourceFilter<'a>],
+  transparent: Option<&'a SourceFilter<'a>>,
+  #[cfg(display_full_error_unstable)]
+  provided_tags: bool,
+}
+
+/// Condition ending the chain walk, see [`FormatOptions::stop_at`] and
+/// [`FormatOptions::stop_when`].
+#[derive(Clone, Copy)]
+struct StopAt<'a> {
+  /// Returns `true` for the source where the walk stops.
+  predicate: &'a SourceFilter<'a>,
+  /// Whether the matching source is printed.
+  inclusive: bool,
+}
+
+/// Predicate on a source, see [`FormatOptions::filter`] and
+/// [`FormatOptions::stop_when`].
+pub type SourceFilter<'a> = dyn Fn(&(dyn Error + 'static)) -> bool + 'a;
+
+/// Transform applied to each message, see [`FormatOptions::transform`].
+///
+/// The first argument is the index of the message among the printed messages,
+/// `0` is the outermost error.
+#[cfg(feature = "alloc")]
+pub type MessageTransform<'a> = dyn Fn(usize, &str) -> Cow<'_, str> + 'a;
+
+impl fmt::Debug for FormatOptions<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut s = f.debug_struct("FormatOptions");
+    s.field("source_first_letter", &self.source_first_letter)
+      .field("strip_trailing_periods", &self.strip_trailing_periods)
+      .field("trim_whitespace", &self.trim_whitespace)
+      .field("strip_error_prefix", &self.strip_error_prefix)
+      .field("per_message_precision", &self.per_message_precision)
+      .field("grapheme_truncation", &self.grapheme_truncation)
+      .field("max_chars", &self.max_chars)
+      .field("debug_messages", &self.debug_messages)
+      .field("resilient", &self.resilient);
+    #[cfg(feature = "alloc")]
+    s.field("transform", &self.transform.map(|_| "Fn"));
+    #[cfg(feature = "alloc")]
+    s.field("redact", &self.redact.len());
+    #[cfg(feature = "alloc")]
+    s.field("detect_double_print", &self.detect_double_print);
+    s.field("filter", &self.filter.map(|_| "Fn"));
+    s.field("stop_inclusive", &self.stop.map(|stop| stop.inclusive));
+    s.field("repeated", &self.repeated.len());
+    s.field("transparent", &self.transparent.map(|_| "Fn"));
+    #[cfg(display_full_error_unstable)]
+    s.field("provided_tags", &self.provided_tags);
+    s.finish()
+  }
+}
+
+/// Case to apply to a letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LetterCase {
+  /// Lowercase, e.g. `Permission denied` becomes `permission denied`
+  Lower,
+  /// Uppercase, e.g. `permission denied` becomes `Permission denied`
+  Upper,
+}
+
+impl<'a> FormatOptions<'a> {
+  /// Create the default options, matching the output of
+  /// [`DisplayFullError`](struct@crate::DisplayFullError).
+  pub const fn new() -> Self {
+    Self {
+      source_first_letter: None,
+      strip_trailing_periods: false,
+      trim_whitespace: false,
+      strip_error_prefix: false,
+      per_message_precision: false,
+      grapheme_truncation: false,
+      max_chars: &[],
+      debug_messages: false,
+      resilient: false,
+      #[cfg(feature = "alloc")]
+      transform: None,
+      #[cfg(feature = "alloc")]
+      redact: &[],
+      #[cfg(feature = "alloc")]
+      detect_double_print: false,
+      filter: None,
+      stop: None,
+      repeated: &[],
+      transparent: None,
+      #[cfg(display_full_error_unstable)]
+      provided_tags: false,
+    }
+  }
+
+  /// Change the case of the first letter of each source message.
+  ///
+  /// The first message of the chain is kept as-is. Using
+  /// [`LetterCase::Lower`] lets chains built from errors with capitalized
+  /// messages read as a single sentence.
+  pub const fn source_first_letter(mut self, case: LetterCase) -> Self {
+    self.source_first_letter = Some(case);
+    self
+  }
+
+  /// Remove the trailing periods of each message.
+  pub const fn strip_trailing_periods(mut self, strip: bool) -> Self {
+    self.strip_trailing_periods = strip;
+    self
+  }
+
+  /// Remove the leading and trailing whitespace of each message.
+  ///
+  /// Some errors end their message with a space or a newline, which corrupts
+  /// the `: ` separators.
+  pub const fn trim_whitespace(mut self, trim: bool) -> Self {
+    self.trim_whitespace = trim;
+    self
+  }
+
+  /// Remove redundant `Error: ` prefixes at the start of each message.
+  ///
+  /// The prefix is matched case-insensitively and repeated prefixes are all
+  /// removed, so a chain reading `upload failed: Error: error: permission
+  /// denied` becomes `upload failed: permission denied`.
+  pub const fn strip_error_prefix(mut self, strip: bool) -> Self {
+    self.strip_error_prefix = strip;
+    self
+  }
+
+  /// Apply the precision of the format string to each message.
+  ///
+  /// By default, the formatter flags are passed to each message, as with
+  /// [`DisplayFullError`](struct@crate::DisplayFullError), unless an option
+  /// rewrites the text of the messages: then they are ignored. With this
+  /// option, the precision (e.g. `{:.80}`) caps the number of chars of each
+  /// message independently, and the other flags are ignored. This keeps all
+  /// the causes visible even when one of them is very long.
+  ///
+  /// Messages are always cut on `char` boundaries, so the output is never
+  /// broken UTF-8.
+  pub const fn per_message_precision(mut self, enabled: bool) -> Self {
+    self.per_message_precision = enabled;
+    self
+  }
+
+  /// Avoid splitting grapheme clusters when truncating messages.
+  ///
+  /// By default, [`per_message_precision`](FormatOptions::per_message_precision)
+  /// cuts messages after the allowed number of `char`s, which may separate a
+  /// letter from its combining accent or split an emoji sequence. With this
+  /// option, the chars extending the last kept char are kept as well, even if
+  /// it exceeds the precision: combining marks, variation selectors, emoji
+  /// modifiers and tags, and the char following a zero width joiner.
+  ///
+  /// This is an approximation of extended grapheme clusters which doesn't
+  /// require the Unicode tables: it doesn't handle Hangul syllables or
+  /// regional indicator pairs (flags).
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// const OPTIONS: FormatOptions<'static> = FormatOptions::new()
+  ///   .per_message_precision(true)
+  ///   .grapheme_truncation(true);
+  ///
+  /// let err = ::std::io::Error::other("caf\u{65}\u{301} closed");
+  /// assert_eq!(format!("{:.4}", err.display_full_with(OPTIONS)), "caf\u{65}\u{301}");
+  /// ```
+  pub const fn grapheme_truncation(mut self, enabled: bool) -> Self {
+    self.grapheme_truncation = enabled;
+    self
+  }
+
+  /// Cap the number of chars of each message depending on its depth.
+  ///
+  /// The limit at index `i` applies to the message at depth `i` in the chain
+  /// (`0` is the outermost error), and the last limit applies to all the
+  /// deeper messages. The depth counts all the sources, including the ones
+  /// skipped by other options such as [`filter`](FormatOptions::filter).
+  /// For example, `&[200, 80]` allows 200 chars for the top message and 80
+  /// for each cause, so the length of the output stays predictable whichever
+  /// layer produced a verbose message. An empty slice disables the limits.
+  ///
+  /// The limits are independent of the formatter flags; if
+  /// [`per_message_precision`](FormatOptions::per_message_precision) is also
+  /// enabled, the smallest limit wins. Messages are cut the same way, see
+  /// [`grapheme_truncation`](FormatOptions::grapheme_truncation).
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// const OPTIONS: FormatOptions<'static> = FormatOptions::new().max_chars(&[6, 4]);
+  ///
+  /// let err = ::std::io::Error::other("upload failed");
+  /// assert_eq!(err.display_full_with(OPTIONS).to_string(), "upload");
+  /// ```
+  pub const fn max_chars(mut self, limits: &'a [usize]) -> Self {
+    self.max_chars = limits;
+    self
+  }
+
+  /// Whether an option rewrites the text of the messages, so the formatter
+  /// flags can't be passed to the messages.
+  fn rewrites_messages(&self) -> bool {
+    #[cfg(feature = "alloc")]
+    if self.transform.is_some() || !self.redact.is_empty() {
+      return true;
+    }
+    self.source_first_letter.is_some()
+      || self.strip_trailing_periods
+      || self.trim_whitespace
+      || self.strip_error_prefix
+      || self.per_message_precision
+      || !self.max_chars.is_empty()
+  }
+
+  /// Get the maximum number of chars of the message at `depth`, combining the
+  /// [`max_chars`](Self::max_chars) limits with the formatter `precision`.
+  fn max_chars_at(&self, depth: usize, precision: Option<usize>) -> Option<usize> {
+    let precision = if self.per_message_precision { precision } else { None };
+    let limit = self.max_chars.get(depth).or(self.max_chars.last()).copied();
+    match (precision, limit) {
+      (Some(a), Some(b)) => Some(a.min(b)),
+      (a, b) => a.or(b),
+    }
+  }
+
+  /// Rewrite each message with a custom transform.
+  ///
+  /// The transform receives the index of the message among the printed
+  /// messages (`0` is the outermost error) and its text, and returns the text to print. This
+  /// enables custom rewriting such as translation or tagging. The returned
+  /// text is then normalized with the other options.
+  ///
+  /// Requires the `alloc` feature.
+  ///
+  /// ```rust
+  /// use ::std::borrow::Cow;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// fn tag(i: usize, message: &str) -> Cow<'_, str> {
+  ///   Cow::Owned(format!("[{i}] {message}"))
+  /// }
+  /// let err = ::std::io::Error::other("permission denied");
+  /// assert_eq!(err.display_full_with(FormatOptions::new().transform(&tag)).to_string(), "[0] permission denied");
+  /// ```
+  #[cfg(feature = "alloc")]
+  pub const fn transform(mut self, transform: &'a MessageTransform<'a>) -> Self {
+    self.transform = Some(transform);
+    self
+  }
+
+  /// Replace every occurrence of the `secrets` with `[REDACTED]`.
+  ///
+  /// Redaction applies to every message, after the
+  /// [`transform`](Self::transform), so tokens or passwords embedded in
+  /// third-party error messages are not printed. Empty secrets are ignored.
+  /// The secrets themselves are not shown by the `Debug` implementation.
+  ///
+  /// Requires the `alloc` feature.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// let err = ::std::io::Error::other("invalid token hunter2");
+  /// let options = FormatOptions::new().redact(&["hunter2"]);
+  /// assert_eq!(err.display_full_with(options).to_string(), "invalid token [REDACTED]");
+  /// ```
+  #[cfg(feature = "alloc")]
+  pub const fn redact(mut self, secrets: &'a [&'a str]) -> Self {
+    self.redact = secrets;
+    self
+  }
+
+  /// Panic in debug builds if a message includes the whole message of its
+  /// source.
+  ///
+  /// This catches errors printing their source in their own message, such as
+  /// `#[error("upload failed: {0}")]` with `thiserror`, which leads to
+  /// duplicated messages in the formatted chain. The check only runs when
+  /// `debug_assertions` are enabled, and does nothing in release builds.
+  /// Sources skipped with [`transparent`](Self::transparent) are intentional
+  /// and are not reported. Empty messages are ignored.
+  ///
+  /// Requires the `alloc` feature.
+  ///
+  /// ```rust,should_panic
+  /// use ::core::fmt;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// #[derive(Debug)]
+  /// struct ReadError(::std::io::Error);
+  ///
+  /// impl fmt::Display for ReadError {
+  ///   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  ///     write!(f, "read failed: {}", self.0)
+  ///   }
+  /// }
+  ///
+  /// impl ::core::error::Error for ReadError {
+  ///   fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+  ///     Some(&self.0)
+  ///   }
+  /// }
+  ///
+  /// let err = ReadError(::std::io::Error::other("permission denied"));
+  /// let options = FormatOptions::new().detect_double_print(true);
+  /// // Panics: "read failed: permission denied" includes "permission denied"
+  /// let _ = err.display_full_with(options).to_string();
+  /// ```
+  #[cfg(feature = "alloc")]
+  pub const fn detect_double_print(mut self, enabled: bool) -> Self {
+    self.detect_double_print = enabled;
+    self
+  }
+
+  /// Print the `Debug` representation of each error after its message, in
+  /// brackets.
+  ///
+  /// This is a verbose mode for debugging sessions, where `Display`
+  /// implementations hide fields needed to understand the failure. The
+  /// `Debug` output is printed as-is, the message options don't apply to it.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// let err = ::std::fmt::Error;
+  /// let options = FormatOptions::new().debug_messages(true);
+  /// assert_eq!(err.display_full_with(options).to_string(), "an error occurred when formatting an argument [Error]");
+  /// ```
+  pub const fn debug_messages(mut self, enabled: bool) -> Self {
+    self.debug_messages = enabled;
+    self
+  }
+
+  /// Print a placeholder for the messages failing to format, and continue
+  /// with the rest of the chain.
+  ///
+  /// By default, a `Display` implementation returning an error aborts the
+  /// whole output. In resilient mode, each message is first formatted to a
+  /// sink to check that it succeeds: if it fails, [`DISPLAY_ERROR`] is
+  /// printed instead. Messages are formatted twice in this mode. Errors
+  /// from the output itself are still returned.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// #[derive(Debug)]
+  /// struct BrokenError;
+  ///
+  /// impl ::core::fmt::Display for BrokenError {
+  ///   fn fmt(&self, _f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+  ///     Err(::core::fmt::Error)
+  ///   }
+  /// }
+  ///
+  /// impl ::core::error::Error for BrokenError {}
+  ///
+  /// let options = FormatOptions::new().resilient(true);
+  /// assert_eq!(BrokenError.display_full_with(options).to_string(), "<display error>");
+  /// ```
+  pub const fn resilient(mut self, enabled: bool) -> Self {
+    self.resilient = enabled;
+    self
+  }
+
+  /// Print the `&str` provided by each error after its message, in
+  /// parentheses.
+  ///
+  /// The tag is requested with the generic member access API
+  /// ([`Error::provide`](core::error::Error::provide)), so errors can attach
+  /// context such as request IDs or SQL statements without including it in
+  /// their message. Only the first `&str` provided by each error is printed.
+  ///
+  /// The generic member access API is unstable: this option requires a nightly
+  /// toolchain and the `display_full_error_unstable` cfg flag (e.g.
+  /// `RUSTFLAGS="--cfg display_full_error_unstable"`).
+  #[cfg(display_full_error_unstable)]
+  pub const fn provided_tags(mut self, enabled: bool) -> Self {
+    self.provided_tags = enabled;
+    self
+  }
+
+  /// Skip the sources rejected by the predicate.
+  ///
+  /// Sources for which the predicate returns `false` are omitted from the
+  /// output, the rest of the chain is still printed. This is intended to hide
+  /// noisy intermediate layers. The outermost error is always printed.
+  ///
+  /// ```rust
+  /// use ::core::error::Error;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// fn not_io(e: &(dyn Error + 'static)) -> bool {
+  ///   !e.is::<::std::io::Error>()
+  /// }
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// assert_eq!(err.display_full_with(FormatOptions::new().filter(&not_io)).to_string(), "permission denied");
+  /// ```
+  pub const fn filter(mut self, filter: &'a SourceFilter<'a>) -> Self {
+    self.filter = Some(filter);
+    self
+  }
+
+  /// Stop at the first source with the type `T`.
+  ///
+  /// The sources below are not printed. The matching source itself is printed
+  /// if `inclusive` is `true`. This is intended to cut off verbose low-level
+  /// chains below domain errors. The outermost error is always printed.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// let err = ::std::io::Error::new(::std::io::ErrorKind::Other, ::std::fmt::Error);
+  /// let options = FormatOptions::new().stop_at::<::std::fmt::Error>(false);
+  /// assert_eq!(err.display_full_with(options).to_string(), "an error occurred when formatting an argument");
+  /// ```
+  pub const fn stop_at<T>(mut self, inclusive: bool) -> Self
+  where
+    T: Error + 'static,
+  {
+    self.stop = Some(StopAt {
+      predicate: &is_type::<T>,
+      inclusive,
+    });
+    self
+  }
+
+  /// Skip the sources with the type `T`, except the first one.
+  ///
+  /// This addresses recursive wrappers re-wrapping the same error type
+  /// repeatedly: only the first source of type `T` is printed. The outermost
+  /// error is always printed, and is not taken into account as its type is
+  /// not known at runtime. It replaces the types set by a previous
+  /// `skip_repeated` or `skip_repeated_types`.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// let options = FormatOptions::new().skip_repeated::<::std::io::Error>();
+  /// assert_eq!(err.display_full_with(options).to_string(), "permission denied");
+  /// ```
+  pub const fn skip_repeated<T>(mut self) -> Self
+  where
+    T: Error + 'static,
+  {
+    self.repeated = &[&is_type::<T>];
+    self
+  }
+
+  /// Skip the sources with an already seen type, among the types matched by
+  /// `types`.
+  ///
+  /// Each predicate identifies a type, usually with
+  /// [`is`](core::error::Error::is). For each predicate, only the first
+  /// matching source is printed, the other ones are skipped. The stable
+  /// `Error` trait doesn't expose the type of an error behind `&dyn Error`, so
+  /// the candidate types must be listed. Only the first
+  /// [`REPEATED_TYPES_LIMIT`] predicates are used.
+  ///
+  /// ```rust
+  /// use ::core::error::Error;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// const OPTIONS: FormatOptions<'static> = FormatOptions::new().skip_repeated_types(&[
+  ///   &|e: &(dyn Error + 'static)| e.is::<::std::io::Error>(),
+  ///   &|e: &(dyn Error + 'static)| e.is::<::core::fmt::Error>(),
+  /// ]);
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// assert_eq!(err.display_full_with(OPTIONS).to_string(), "permission denied");
+  /// ```
+  pub const fn skip_repeated_types(mut self, types: &'a [&'a SourceFilter<'a>]) -> Self {
+    self.repeated = types;
+    self
+  }
+
+  /// Skip the source of the errors with the type `T`, which already include
+  /// it in their message.
+  ///
+  /// This is the case for wrappers formatting their source in their own
+  /// message, such as `#[error("upload failed: {0}")]` with `thiserror`.
+  /// Registering the type skips the redundant source deterministically,
+  /// instead of relying on heuristics comparing messages. Only the direct
+  /// source is skipped, the rest of the chain is printed.
+  ///
+  /// Only the sources are checked: the outermost error is not taken into
+  /// account as its type is not known at runtime. A single condition is kept,
+  /// it replaces the one set by a previous `transparent` or
+  /// `transparent_when`; use [`transparent_when`](Self::transparent_when) to
+  /// register multiple types.
+  ///
+  /// ```rust
+  /// use ::core::fmt;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// #[derive(Debug)]
+  /// struct ReadError(::std::io::Error);
+  ///
+  /// impl fmt::Display for ReadError {
+  ///   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  ///     write!(f, "read failed: {}", self.0)
+  ///   }
+  /// }
+  ///
+  /// impl ::core::error::Error for ReadError {
+  ///   fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+  ///     Some(&self.0)
+  ///   }
+  /// }
+  ///
+  /// #[derive(Debug)]
+  /// struct UploadError(ReadError);
+  ///
+  /// impl fmt::Display for UploadError {
+  ///   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  ///     f.write_str("upload failed")
+  ///   }
+  /// }
+  ///
+  /// impl ::core::error::Error for UploadError {
+  ///   fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+  ///     Some(&self.0)
+  ///   }
+  /// }
+  ///
+  /// let err = UploadError(ReadError(::std::io::Error::other("permission denied")));
+  /// let options = FormatOptions::new().transparent::<ReadError>();
+  /// assert_eq!(err.display_full_with(options).to_string(), "upload failed: read failed: permission denied");
+  /// ```
+  pub const fn transparent<T>(mut self) -> Self
+  where
+    T: Error + 'static,
+  {
+    self.transparent = Some(&is_type::<T>);
+    self
+  }
+
+  /// Skip the source of the errors matching the predicate, which already
+  /// include it in their message.
+  ///
+  /// This generalizes [`transparent`](Self::transparent) to multiple types,
+  /// or to conditions that can't be expressed with a type alone.
+  ///
+  /// ```rust
+  /// use ::core::error::Error;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// fn is_wrapper(e: &(dyn Error + 'static)) -> bool {
+  ///   e.is::<::std::io::Error>() || e.is::<::core::fmt::Error>()
+  /// }
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// let options = FormatOptions::new().transparent_when(&is_wrapper);
+  /// assert_eq!(err.display_full_with(options).to_string(), "permission denied");
+  /// ```
+  pub const fn transparent_when(mut self, predicate: &'a SourceFilter<'a>) -> Self {
+    self.transparent = Some(predicate);
+    self
+  }
+
+  /// Stop at the first source matching the predicate.
+  ///
+  /// This generalizes [`stop_at`](Self::stop_at) to conditions that can't be
+  /// expressed with a type alone. The sources below are not printed. The
+  /// matching source itself is printed if `inclusive` is `true`. The outermost
+  /// error is always printed. A single stop condition is kept, it replaces the
+  /// one set by a previous `stop_at` or `stop_when`.
+  ///
+  /// ```rust
+  /// use ::core::error::Error;
+  /// use ::display_full_error::{DisplayFullErrorExt, FormatOptions};
+  ///
+  /// fn is_internal(e: &(dyn Error + 'static)) -> bool {
+  ///   e.to_string().starts_with("internal")
+  /// }
+  ///
+  /// let err = ::std::io::Error::other("permission denied");
+  /// let options = FormatOptions::new().stop_when(&is_internal, false);
+  /// assert_eq!(err.display_full_with(options).to_string(), "permission denied");
+  /// ```
+  pub const fn stop_when(mut self, predicate: &'a SourceFilter<'a>, inclusive: bool) -> Self {
+    self.stop = Some(StopAt { predicate, inclusive });
+    self
+  }
+}
+
+/// Formatting wrapper to display errors with their sources, with custom
+/// [options](FormatOptions).
+///
+/// Error messages are formatted on a single line, separated with `: `; up to
+/// [`MESSAGE_LIMIT`] messages per chain are printed, after which a single
+/// `: ...` is printed. Each message is normalized according to the options.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayFullErrorWith<'e, E>(pub &'e E, pub FormatOptions<'e>)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorWith<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let options = &self.1;
+    #[cfg(all(feature = "alloc", debug_assertions))]
+    if options.detect_double_print {
+      check_double_print(self.0, options);
+    }
+    let precision = f.precision();
+    write_message(f, options, 0, self.0, options.max_chars_at(0, precision))?;
+    write_debug(f, options, self.0)?;
+    #[cfg(display_full_error_unstable)]
+    write_provided_tag(f, options, self.0)?;
+    // The limit applies to the visited messages, so filtering out every
+    // source of a cyclic chain still terminates.
+    let mut visited: u16 = 1;
+    let mut printed: usize = 1;
+    // Bit `i` is set once a source matching `options.repeated[i]` is printed
+    let mut repeated_seen: u64 = 0;
+    let mut skip_source = false;
+    for e in sources(self.0) {
+      if visited >= MESSAGE_LIMIT {
+        f.write_str(": ...")?;
+        return Ok(());
+      }
+      visited = visited.saturating_add(1);
+      let stop = options.stop.filter(|stop| (stop.predicate)(e));
+      if stop.is_some_and(|stop| !stop.inclusive) {
+        break;
+      }
+      if ::core::mem::take(&mut skip_source) {
+        // The source is already printed by the message of its parent
+        if stop.is_some() {
+          break;
+        }
+        continue;
+      }
+      if let Some(filter) = options.filter {
+        if !filter(e) {
+          continue;
+        }
+      }
+      let repeated = options
+        .repeated
+        .iter()
+        .take(REPEATED_TYPES_LIMIT)
+        .position(|is_type| is_type(e));
+      if let Some(index) = repeated {
+        let bit = u32::try_from(index).ok().and_then(|i| 1u64.checked_shl(i)).unwrap_or(0);
+        if repeated_seen & bit != 0 {
+          continue;
+        }
+        repeated_seen |= bit;
+      }
+      f.write_str(": ")?;
+      let depth = usize::from(visited.saturating_sub(1));
+      write_message(f, options, printed, e, options.max_chars_at(depth, precision))?;
+      write_debug(f, options, e)?;
+      skip_source = options.transparent.is_some_and(|transparent| transparent(e));
+      #[cfg(display_full_error_unstable)]
+      write_provided_tag(f, options, e)?;
+      printed = printed.saturating_add(1);
+      if stop.is_some() {
+        break;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Panic if a message of the chain includes the whole message of its source.
+#[cfg(all(feature = "alloc", debug_assertions))]
+fn check_double_print<E>(error: &E, options: &FormatOptions<'_>)
+where
+  E: Error + ?Sized,
+{
+  use ::alloc::string::ToString;
+
+  let mut parent = error.to_string();
+  // The type of the outermost error is not known at runtime
+  let mut parent_transparent = false;
+  for (depth, e) in sources(error).enumerate().take(usize::from(MESSAGE_LIMIT)) {
+    let message = e.to_string();
+    debug_assert!(
+      parent_transparent || message.is_empty() || !parent.contains(message.as_str()),
+      "error message at depth {depth} includes the message of its source: {parent:?}"
+    );
+    parent = message;
+    parent_transparent = options.transparent.is_some_and(|transparent| transparent(e));
+  }
+}
+
+/// Check if `error` has the type `T`.
+fn is_type<T>(error: &(dyn Error + 'static)) -> bool
+where
+  T: Error + 'static,
+{
+  error.is::<T>()
+}
+
+/// Write a single message of the chain, applying the options.
+fn write_message<M>(
+  f: &mut fmt::Formatter<'_>,
+  options: &FormatOptions<'_>,
+  index: usize,
+  message: &M,
+  max_chars: Option<usize>,
+) -> fmt::Result
+where
+  M: fmt::Display + ?Sized,
+{
+  if options.resilient && write!(Discard, "{message}").is_err() {
+    return f.write_str(DISPLAY_ERROR);
+  }
+  if !options.rewrites_messages() {
+    return fmt::Display::fmt(message, f);
+  }
+  let first_letter = if index == 0 { None } else { options.source_first_letter };
+  let writer = MessageWriter::new(f, options, first_letter, max_chars);
+  #[cfg(feature = "alloc")]
+  if options.transform.is_some() || !options.redact.is_empty() {
+    use ::alloc::string::ToString;
+
+    let message = message.to_string();
+    let message = match options.transform {
+      Some(transform) => transform(index, &message),
+      None => Cow::Borrowed(message.as_str()),
+    };
+    return writer.write_message(&*redact(message, options.redact));
+  }
+  writer.write_message(message)
+}
+
+/// Write the `Debug` representation of `error` in brackets, if enabled.
+fn write_debug<E>(f: &mut fmt::Formatter<'_>, options: &FormatOptions<'_>, error: &E) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  if options.debug_messages {
+    write!(f, " [{error:?}]")?;
+  }
+  Ok(())
+}
+
+/// Write the `&str` provided by `error` in parentheses, if enabled.
+#[cfg(display_full_error_unstable)]
+fn write_provided_tag<E>(f: &mut fmt::Formatter<'_>, options: &FormatOptions<'_>, error: &E) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  if options.provided_tags {
+    if let Some(tag) = ::core::error::request_ref::<str>(error) {
+      write!(f, " ({tag})")?;
+    }
+  }
+  Ok(())
+}
+
+/// Maximum number of types tracked by [`FormatOptions::skip_repeated_types`].
+pub const REPEATED_TYPES_LIMIT: usize = 64;
+
+/// Placeholder printed for the messages failing to format, see
+/// [`FormatOptions::resilient`].
+pub const DISPLAY_ERROR: &str = "<display error>";
+
+/// Writer discarding its input.
+struct Discard;
+
+impl Write for Discard {
+  fn write_str(&mut self, _s: &str) -> fmt::Result {
+    Ok(())
+  }
+}
+
+/// Replace every occurrence of the `secrets` in `message` with [`REDACTED`].
+#[cfg(feature = "alloc")]
+fn redact<'m>(message: Cow<'m, str>, secrets: &[&str]) -> Cow<'m, str> {
+  let mut message = message;
+  for secret in secrets {
+    if !secret.is_empty() && message.contains(secret) {
+      message = Cow::Owned(message.replace(secret, REDACTED));
+    }
+  }
+  message
+}
+
+/// Replacement text for the secrets removed by [`FormatOptions::redact`].
+#[cfg(feature = "alloc")]
+const REDACTED: &str = "[REDACTED]";
+
+/// Capacity of the buffer holding the trailing chars of a message, in runs of
+/// the same char.
+///
+/// Only the last runs are removed from messages ending with more alternating
+/// runs of trailing chars.
+const PENDING_RUNS: usize = 64;
+
+/// Prefix removed by [`FormatOptions::strip_error_prefix`], in lowercase
+const ERROR_PREFIX: &[u8; 6] = b"error:";
+
+/// Zero width joiner, gluing the surrounding chars in a single grapheme cluster
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// Check if `c` extends the grapheme cluster of the previous char.
+///
+/// This covers the combining marks, variation selectors, emoji modifiers and
+/// tags, see [`FormatOptions::grapheme_truncation`].
+fn extends_grapheme(c: char) -> bool {
+  matches!(
+    c,
+    '\u{0300}'..='\u{036F}'
+      | '\u{1AB0}'..='\u{1AFF}'
+      | '\u{1DC0}'..='\u{1DFF}'
+      | '\u{20D0}'..='\u{20FF}'
+      | '\u{FE00}'..='\u{FE0F}'
+      | '\u{FE20}'..='\u{FE2F}'
+      | ZERO_WIDTH_JOINER
+      | '\u{1F3FB}'..='\u{1F3FF}'
+      | '\u{E0020}'..='\u{E007F}'
+      | '\u{E0100}'..='\u{E01EF}'
+  )
+}
+
+/// Writer applying the normalization options to a single message.
+struct MessageWriter<'o, W> {
+  inner: W,
+  options: &'o FormatOptions<'o>,
+  /// Whether leading whitespace must still be skipped
+  trim_start: bool,
+  /// Whether the start of the message must still be matched against the error prefix
+  match_prefix: bool,
+  /// Start of the message matching the error prefix, not forwarded yet
+  prefix: [u8; ERROR_PREFIX.len()],
+  prefix_len: usize,
+  /// Whether a prefix was removed, so the following whitespace must be skipped
+  prefix_removed: bool,
+  /// Case to apply to the next letter, cleared once the first char is written
+  first_letter: Option<LetterCase>,
+  /// Trailing chars written but not forwarded yet, as they may end the message,
+  /// as runs of the same char
+  pending: [(char, u32); PENDING_RUNS],
+  pending_len: usize,
+  /// Maximum number of chars still allowed for this message
+  remaining_chars: Option<usize>,
+  /// Whether the last forwarded char is a zero width joiner
+  join_next: bool,
+  /// Whether the message was cut, so the rest must be dropped
+  cut: bool,
+}
+
+impl<'o, W> MessageWriter<'o, W>
+where
+  W: Write,
+{
+  fn new(inner: W, options: &'o FormatOptions, first_letter: Option<LetterCase>, max_chars: Option<usize>) -> Self {
+    Self {
+      inner,
+      options,
+      trim_start: options.trim_whitespace,
+      match_prefix: options.strip_error_prefix,
+      prefix: [0; ERROR_PREFIX.len()],
+      prefix_len: 0,
+      prefix_removed: false,
+      first_letter,
+      pending: [('\0', 0); PENDING_RUNS],
+      pending_len: 0,
+      remaining_chars: max_chars,
+      join_next: false,
+      cut: false,
+    }
+  }
+
+  /// Write the full message, then apply the end-of-message normalization.
+  fn write_message<M>(mut self, message: &M) -> fmt::Result
+  where
+    M: fmt::Display + ?Sized,
+  {
+    write!(self, "{message}")?;
+    // A partial prefix match is the whole message
+    self.flush_prefix()
+    // Pending trailing chars are dropped
+  }
+
+  /// Match the start of the message against the error prefix.
+  ///
+  /// Returns the remaining part of `s`, once the prefix matching is complete.
+  fn strip_prefix<'s>(&mut self, mut s: &'s str) -> Result<&'s str, fmt::Error> {
+    'prefix: while self.match_prefix {
+      if self.prefix_removed {
+        s = s.trim_start();
+        if s.is_empty() {
+          return Ok(s);
+        }
+        self.prefix_removed = false;
+      }
+      for (i, c) in s.char_indices() {
+        let expected = ERROR_PREFIX.get(self.prefix_len).copied().map(char::from);
+        match (self.prefix.get_mut(self.prefix_len), expected) {
+          (Some(slot), Some(expected)) if c.to_ascii_lowercase() == expected => {
+            // The expected char is ASCII, so is `c`
+            *slot = c as u8;
+            self.prefix_len = self.prefix_len.saturating_add(1);
+            if self.prefix_len == ERROR_PREFIX.len() {
+              self.prefix_len = 0;
+              self.prefix_removed = true;
+              s = s.get(i.saturating_add(1)..).unwrap_or_default();
+              continue 'prefix;
+            }
+          }
+          _ => {
+            self.flush_prefix()?;
+            return Ok(s.get(i..).unwrap_or_default());
+          }
+        }
+      }
+      return Ok("");
+    }
+    Ok(s)
+  }
+
+  /// Stop matching the error prefix, forwarding the partially matched prefix.
+  fn flush_prefix(&mut self) -> fmt::Result {
+    self.match_prefix = false;
+    let prefix = self.prefix;
+    let prefix = prefix.get(..self.prefix_len).unwrap_or_default();
+    self.prefix_len = 0;
+    // Only ASCII chars are buffered, so this is always valid UTF-8
+    self.write_text(::core::str::from_utf8(prefix).unwrap_or_default())
+  }
+
+  /// Write the text of the message, after the leading whitespace and prefixes.
+  fn write_text(&mut self, mut s: &str) -> fmt::Result {
+    if let Some(case) = self.first_letter {
+      let first = match s.chars().next() {
+        Some(first) => first,
+        None => return Ok(()),
+      };
+      self.first_letter = None;
+      s = s.get(first.len_utf8()..).unwrap_or_default();
+      let mut buf = [0u8; 4];
+      match case {
+        LetterCase::Lower => {
+          for c in first.to_lowercase() {
+            self.write_body(c.encode_utf8(&mut buf))?;
+          }
+        }
+        LetterCase::Upper => {
+          for c in first.to_uppercase() {
+            self.write_body(c.encode_utf8(&mut buf))?;
+          }
+        }
+      }
+    }
+    self.write_body(s)
+  }
+
+  /// Whether `c` must be removed when it is part of the end of the message
+  fn is_trailing(&self, c: char) -> bool {
+    (self.options.strip_trailing_periods && c == '.') || (self.options.trim_whitespace && c.is_whitespace())
+  }
+
+  fn write_body(&mut self, s: &str) -> fmt::Result {
+    let trimmed = s.trim_end_matches(|c| self.is_trailing(c));
+    let trailing = s.get(trimmed.len()..).unwrap_or_default();
+    if !trimmed.is_empty() {
+      self.flush_pending()?;
+      self.forward(trimmed)?;
+    }
+    for c in trailing.chars() {
+      if !self.push_pending(c) {
+        self.flush_pending()?;
+        self.push_pending(c);
+      }
+    }
+    Ok(())
+  }
+
+  /// Append `c` to the pending trailing chars, returning `false` if the buffer
+  /// is full.
+  fn push_pending(&mut self, c: char) -> bool {
+    if let Some((last, count)) = self.pending_len.checked_sub(1).and_then(|i| self.pending.get_mut(i)) {
+      if *last == c && *count < u32::MAX {
+        *count = count.saturating_add(1);
+        return true;
+      }
+    }
+    match self.pending.get_mut(self.pending_len) {
+      Some(run) => {
+        *run = (c, 1);
+        self.pending_len = self.pending_len.saturating_add(1);
+        true
+      }
+      None => false,
+    }
+  }
+
+  fn flush_pending(&mut self) -> fmt::Result {
+    let pending = self.pending;
+    let pending = pending.get(..self.pending_len).unwrap_or_default();
+    self.pending_len = 0;
+    let mut buf = [0u8; 4];
+    for &(c, count) in pending {
+      let c: &str = c.encode_utf8(&mut buf);
+      for _ in 0..count {
+        self.forward(c)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Write to the inner writer, within the char limit of the message
+  fn forward(&mut self, s: &str) -> fmt::Result {
+    let Some(mut remaining) = self.remaining_chars else {
+      return self.inner.write_str(s);
+    };
+    if self.cut {
+      return Ok(());
+    }
+    let mut end = 0;
+    for (i, c) in s.char_indices() {
+      if remaining == 0 {
+        if !(self.options.grapheme_truncation && (self.join_next || extends_grapheme(c))) {
+          self.cut = true;
+          break;
+        }
+      } else {
+        remaining = remaining.saturating_sub(1);
+      }
+      self.join_next = c == ZERO_WIDTH_JOINER;
+      end = i.saturating_add(c.len_utf8());
+    }
+    self.remaining_chars = Some(remaining);
+    self.inner.write_str(s.get(..end).unwrap_or_default())
+  }
+}
+
+impl<W> Write for MessageWriter<'_, W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, mut s: &str) -> fmt::Result {
+    if self.trim_start {
+      s = s.trim_start();
+      if s.is_empty() {
+        return Ok(());
+      }
+      self.trim_start = false;
+    }
+    if self.match_prefix {
+      s = self.strip_prefix(s)?;
+    }
+    self.write_text(s)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{message_chain, LimitError, MessageError, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::boxed::Box;
+  use ::alloc::format;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn default_options_match_display_full() {
+    let input = message_chain(&["Upload failed.", "Permission denied."]);
+    let actual: String = input.display_full_with(FormatOptions::new()).to_string();
+    let expected = input.display_full().to_string();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn default_options_forward_flags() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let options = FormatOptions::new();
+    assert_eq!(
+      format!("{:>20}", input.display_full_with(options)),
+      format!("{:>20}", input.display_full())
+    );
+    assert_eq!(
+      format!("{:.4}", input.display_full_with(options)),
+      format!("{:.4}", input.display_full())
+    );
+    assert_eq!(format!("{:.4}", input.display_full_with(options)), "uplo: perm");
+    let options = FormatOptions::new().trim_whitespace(true);
+    assert_eq!(
+      format!("{:.4}", input.display_full_with(options)),
+      "upload failed: permission denied"
+    );
+  }
+
+  #[test]
+  fn lowercase_sources_and_strip_periods() {
+    let input = message_chain(&["Upload failed.", "Connection reset...", "Permission denied."]);
+    let options = FormatOptions::new()
+      .source_first_letter(LetterCase::Lower)
+      .strip_trailing_periods(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("Upload failed: connection reset: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn strip_periods_keeps_inner_periods() {
+    let input = message_chain(&["invalid version 1.2.", "expected 1.3"]);
+    let options = FormatOptions::new().strip_trailing_periods(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("invalid version 1.2: expected 1.3");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn trim_whitespace() {
+    let input = message_chain(&["  upload failed \n", "\tpermission denied\n"]);
+    let options = FormatOptions::new().trim_whitespace(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn trim_whitespace_keeps_inner_whitespace() {
+    let input = message_chain(&["invalid  name . \n"]);
+    let options = FormatOptions::new().trim_whitespace(true).strip_trailing_periods(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("invalid  name");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn trim_long_trailing_run() {
+    let padding = " ".repeat(100);
+    let trailing: &'static str = Box::leak(format!("upload failed{padding}\n\n{padding}").into_boxed_str());
+    let inner: &'static str = Box::leak(format!("permission{padding}denied{}", ".".repeat(100)).into_boxed_str());
+    let input = message_chain(&[trailing, inner]);
+    let options = FormatOptions::new().trim_whitespace(true).strip_trailing_periods(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = format!("upload failed: permission{padding}denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn strip_error_prefix() {
+    let input = message_chain(&["upload failed", "Error: error:permission denied", "error: "]);
+    let options = FormatOptions::new().strip_error_prefix(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("upload failed: permission denied: ");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn strip_error_prefix_keeps_other_words() {
+    let input = message_chain(&["errors occurred", " Err"]);
+    let options = FormatOptions::new().strip_error_prefix(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("errors occurred:  Err");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  #[cfg(display_full_error_unstable)]
+  fn provided_tags() {
+    use crate::tests::QueryError;
+
+    let options = FormatOptions::new().provided_tags(true);
+    let actual: String = QueryError.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("query failed (SELECT 1)"));
+    let actual: String = QueryError.display_full_with(FormatOptions::new()).to_string();
+    assert_eq!(actual, String::from("query failed"));
+  }
+
+  #[test]
+  fn per_message_precision() {
+    let input = message_chain(&["upload failed", "connection reset by peer", "permission denied"]);
+    let options = FormatOptions::new().per_message_precision(true);
+    let actual: String = format!("{:.10}", input.display_full_with(options));
+    let expected = String::from("upload fai: connection: permission");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn per_message_precision_multibyte() {
+    let input = message_chain(&["échec de l'envoi", "accès refusé"]);
+    let options = FormatOptions::new().per_message_precision(true);
+    let actual: String = format!("{:.5}", input.display_full_with(options));
+    let expected = String::from("échec: accès");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn max_chars_per_depth() {
+    let input = message_chain(&["upload failed", "connection reset by peer", "permission denied"]);
+    let options = FormatOptions::new().max_chars(&[6, 10]);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("upload: connection: permission"));
+    let options = options.per_message_precision(true);
+    let actual: String = format!("{:.8}", input.display_full_with(options));
+    assert_eq!(actual, String::from("upload: connecti: permissi"));
+  }
+
+  #[test]
+  fn max_chars_by_chain_depth() {
+    fn not_connection(e: &(dyn Error + 'static)) -> bool {
+      !e.to_string().starts_with("connection")
+    }
+
+    let input = message_chain(&["upload failed", "connection reset by peer", "permission denied"]);
+    let options = FormatOptions::new().max_chars(&[6, 20, 10]).filter(&not_connection);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("upload: permission"));
+  }
+
+  #[test]
+  fn debug_messages() {
+    let input = UploadError::Permission(PermissionError);
+    let options = FormatOptions::new().debug_messages(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("upload failed [Permission(PermissionError)]: permission denied [PermissionError]");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn resilient() {
+    #[derive(Debug)]
+    struct BrokenError(PermissionError);
+
+    impl fmt::Display for BrokenError {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("partial")?;
+        Err(fmt::Error)
+      }
+    }
+
+    impl Error for BrokenError {
+      fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+      }
+    }
+
+    let input = BrokenError(PermissionError);
+    let mut actual = String::new();
+    assert!(write!(actual, "{}", input.display_full_with(FormatOptions::new())).is_err());
+    let options = FormatOptions::new()
+      .resilient(true)
+      .source_first_letter(LetterCase::Upper);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("<display error>: Permission denied"));
+  }
+
+  #[test]
+  fn grapheme_truncation() {
+    let input = message_chain(&[
+      "e\u{301}chec",
+      "\u{1F469}\u{200D}\u{1F4BB} offline",
+      "\u{1F44B}\u{1F3FD}!",
+    ]);
+    let options = FormatOptions::new().per_message_precision(true);
+    let actual: String = format!("{:.1}", input.display_full_with(options));
+    assert_eq!(actual, String::from("e: \u{1F469}: \u{1F44B}"));
+    let options = options.grapheme_truncation(true);
+    let actual: String = format!("{:.1}", input.display_full_with(options));
+    let expected = String::from("e\u{301}: \u{1F469}\u{200D}\u{1F4BB}: \u{1F44B}\u{1F3FD}");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn transform_messages() {
+    fn tag(i: usize, message: &str) -> Cow<'_, str> {
+      if i == 0 {
+        Cow::Borrowed(message)
+      } else {
+        Cow::Owned(format!("[cause {i}] {message}"))
+      }
+    }
+    let input = message_chain(&["upload failed", "Permission denied."]);
+    let options = FormatOptions::new().transform(&tag).strip_trailing_periods(true);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("upload failed: [cause 1] Permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn skip_repeated_type() {
+    let input = message_chain(&["upload failed", "request failed", "request failed", "permission denied"]);
+    let actual: String = input
+      .display_full_with(FormatOptions::new().skip_repeated::<MessageError>())
+      .to_string();
+    assert_eq!(actual, String::from("upload failed: request failed"));
+    let actual: String = input
+      .display_full_with(FormatOptions::new().skip_repeated::<PermissionError>())
+      .to_string();
+    let expected = String::from("upload failed: request failed: request failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn skip_repeated_types() {
+    let input = UploadError::Permission(PermissionError);
+    let options = FormatOptions::new().skip_repeated_types(&[
+      &|e: &(dyn Error + 'static)| e.is::<MessageError>(),
+      &|e: &(dyn Error + 'static)| e.is::<PermissionError>(),
+    ]);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+    let input = message_chain(&["job failed", "upload failed", "retry failed", "permission denied"]);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("job failed: upload failed"));
+  }
+
+  #[test]
+  fn transparent_source() {
+    let input = message_chain(&[
+      "upload failed",
+      "read failed: denied",
+      "denied",
+      "disk failed: full",
+      "full",
+    ]);
+    let actual: String = input
+      .display_full_with(FormatOptions::new().transparent::<MessageError>())
+      .to_string();
+    assert_eq!(
+      actual,
+      String::from("upload failed: read failed: denied: disk failed: full")
+    );
+    let options = FormatOptions::new()
+      .transparent::<MessageError>()
+      .stop_at::<MessageError>(true);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("upload failed: read failed: denied"));
+  }
+
+  #[test]
+  fn transparent_multiple_types() {
+    fn is_wrapper(e: &(dyn Error + 'static)) -> bool {
+      e.is::<MessageError>() || e.is::<UploadError>()
+    }
+
+    let input = message_chain(&["job failed", "read failed: denied", "denied"]);
+    let options = FormatOptions::new().transparent_when(&is_wrapper);
+    let actual: String = input.display_full_with(options).to_string();
+    assert_eq!(actual, String::from("job failed: read failed: denied"));
+  }
+
+  #[test]
+  #[cfg(all(feature = "alloc", debug_assertions))]
+  fn detect_double_print() {
+    let options = FormatOptions::new().detect_double_print(true);
+    let input = message_chain(&["upload failed", "read failed", "", "denied"]);
+    assert_eq!(
+      input.display_full_with(options).to_string(),
+      String::from("upload failed: read failed: : denied")
+    );
+    let input = message_chain(&["upload failed", "read failed: denied", "denied"]);
+    let options = options.transparent::<MessageError>();
+    assert_eq!(
+      input.display_full_with(options).to_string(),
+      String::from("upload failed: read failed: denied")
+    );
+  }
+
+  #[test]
+  #[cfg(all(feature = "alloc", debug_assertions))]
+  #[should_panic(expected = "error message at depth 1 includes the message of its source")]
+  fn detect_double_print_panics() {
+    let options = FormatOptions::new().detect_double_print(true);
+    let input = message_chain(&["upload failed", "read failed: denied", "denied"]);
+    let _ = input.display_full_with(options).to_string();
+  }
+
+  #[test]
+  fn filter_sources() {
+    fn not_noise(e: &(dyn Error + 'static)) -> bool {
+      e.to_string() != "request failed"
+    }
+    let input = message_chain(&["upload failed", "request failed", "permission denied"]);
+    let actual: String = input
+      .display_full_with(FormatOptions::new().filter(&not_noise))
+      .to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn redact_secrets() {
+    let input = message_chain(&["login failed for admin@example.com", "token abc123 expired, abc123"]);
+    let options = FormatOptions::new().redact(&["abc123", "admin@example.com", ""]);
+    let actual: String = input.display_full_with(options).to_string();
+    let expected = String::from("login failed for [REDACTED]: token [REDACTED] expired, [REDACTED]");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn stop_at_type() {
+    let input = UploadError::Permission(PermissionError);
+    let exclusive: String = input
+      .display_full_with(FormatOptions::new().stop_at::<PermissionError>(false))
+      .to_string();
+    assert_eq!(exclusive, String::from("upload failed"));
+    let inclusive: String = input
+      .display_full_with(FormatOptions::new().stop_at::<PermissionError>(true))
+      .to_string();
+    assert_eq!(inclusive, String::from("upload failed: permission denied"));
+    let unmatched: String = input
+      .display_full_with(FormatOptions::new().stop_at::<LimitError>(false))
+      .to_string();
+    assert_eq!(unmatched, String::from("upload failed: permission denied"));
+  }
+
+  #[test]
+  fn stop_when_predicate() {
+    fn is_internal(e: &(dyn Error + 'static)) -> bool {
+      e.to_string().starts_with("internal")
+    }
+    let input = message_chain(&["upload failed", "internal error", "socket closed"]);
+    let exclusive: String = input
+      .display_full_with(FormatOptions::new().stop_when(&is_internal, false))
+      .to_string();
+    assert_eq!(exclusive, String::from("upload failed"));
+    let inclusive: String = input
+      .display_full_with(FormatOptions::new().stop_when(&is_internal, true))
+      .to_string();
+    assert_eq!(inclusive, String::from("upload failed: internal error"));
+  }
+}
@@ -0,0 +1,80 @@
+//! Formatting of panic payloads.
+
+use crate::{DisplayFullError, Error};
+use ::alloc::boxed::Box;
+use ::alloc::string::String;
+use ::core::any::Any;
+use ::core::fmt;
+
+/// Formatting wrapper for panic payloads, as returned by `catch_unwind` or
+/// `JoinHandle::join`.
+///
+/// String payloads (`&'static str` and `String`, as created by `panic!`) are
+/// printed as-is. Boxed error payloads (`Box<dyn Error>`, with or without
+/// `Send` and `Sync`) are printed with their full chain. Other payloads are
+/// printed as `Box<dyn Any>`, as with the default panic hook.
+///
+/// Make sure to pass the payload itself and not the box containing it, using
+/// `&*payload`.
+///
+/// Requires the `alloc` feature.
+///
+/// ```rust
+/// use ::display_full_error::DisplayPanicPayload;
+///
+/// let payload = ::std::panic::catch_unwind(|| panic!("upload failed")).unwrap_err();
+/// assert_eq!(DisplayPanicPayload(&*payload).to_string(), "upload failed");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayPanicPayload<'p>(pub &'p (dyn Any + Send));
+
+impl fmt::Display for DisplayPanicPayload<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let payload = self.0;
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+      f.write_str(message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+      f.write_str(message)
+    } else if let Some(e) = payload.downcast_ref::<Box<dyn Error + Send + Sync>>() {
+      fmt::Display::fmt(&DisplayFullError(&**e), f)
+    } else if let Some(e) = payload.downcast_ref::<Box<dyn Error + Send>>() {
+      fmt::Display::fmt(&DisplayFullError(&**e), f)
+    } else if let Some(e) = payload.downcast_ref::<Box<dyn Error>>() {
+      fmt::Display::fmt(&DisplayFullError(&**e), f)
+    } else {
+      f.write_str("Box<dyn Any>")
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DisplayPanicPayload;
+  use crate::tests::message_chain;
+  use crate::Error;
+  use ::alloc::boxed::Box;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn string_payload() {
+    let payload: Box<dyn ::core::any::Any + Send> = Box::new(String::from("upload failed"));
+    assert_eq!(
+      DisplayPanicPayload(&*payload).to_string(),
+      String::from("upload failed")
+    );
+  }
+
+  #[test]
+  fn error_payload() {
+    let error: Box<dyn Error + Send + Sync> = Box::new(message_chain(&["upload failed", "permission denied"]));
+    let payload: Box<dyn ::core::any::Any + Send> = Box::new(error);
+    let actual = DisplayPanicPayload(&*payload).to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+  }
+
+  #[test]
+  fn unknown_payload() {
+    let payload: Box<dyn ::core::any::Any + Send> = Box::new(42u8);
+    assert_eq!(DisplayPanicPayload(&*payload).to_string(), String::from("Box<dyn Any>"));
+  }
+}
@@ -0,0 +1,73 @@
+//! Percent-encoding output mode, for URL components.
+
+use crate::{DisplayFullError, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the full error chain, percent-encoded.
+///
+/// The output is the same as [`DisplayFullError`], with every byte except the
+/// unreserved characters of [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2.3)
+/// (`A-Z`, `a-z`, `0-9`, `-`, `.`, `_`, `~`) encoded as `%XX`. The output can
+/// be used as-is in a URL query parameter or path segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorPercentEncoded<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorPercentEncoded<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(PercentEncode(f), "{}", DisplayFullError(self.0))
+  }
+}
+
+/// Writer percent-encoding the bytes written to the inner writer.
+struct PercentEncode<W>(W);
+
+impl<W> Write for PercentEncode<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() {
+      if is_unreserved(c) {
+        self.0.write_char(c)?;
+      } else {
+        let mut buf = [0u8; 4];
+        for byte in c.encode_utf8(&mut buf).bytes() {
+          write!(self.0, "%{byte:02X}")?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+fn is_unreserved(c: char) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn percent_encoded_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_percent_encoded().to_string();
+    let expected = String::from("upload%20failed%3A%20permission%20denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn percent_encoded_non_ascii() {
+    let input = message_chain(&["café&co=1"]);
+    let actual: String = input.display_percent_encoded().to_string();
+    let expected = String::from("caf%C3%A9%26co%3D1");
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,184 @@
+//! Postcard-compatible encoding of the chain, for `no_std` devices.
+
+use crate::{chain_len, Error, IndexedMessages};
+use ::core::fmt::{self, Write};
+
+/// Encode the messages of the chain into `buf`, in the
+/// [postcard](https://postcard.jamesmunns.com/) wire format.
+///
+/// The output is the encoding of a sequence of strings (such as
+/// `Vec<String>`): the number of messages as a varint, followed by each
+/// message as a varint byte length and its UTF-8 bytes. The chain is encoded
+/// up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the
+/// sequence ends with a `"..."` message. This is intended to transmit chains
+/// over serial or CAN links from `no_std` devices to a host decoding them with
+/// `postcard::from_bytes::<Vec<String>>`.
+///
+/// Returns the number of bytes written. Messages are formatted twice, to
+/// compute their length before writing them.
+///
+/// ```rust
+/// use ::display_full_error::encode_postcard_into;
+///
+/// let err = ::std::io::Error::other("denied");
+/// let mut buf = [0u8; 16];
+/// let len = encode_postcard_into(&err, &mut buf).expect("buffer is large enough");
+/// assert_eq!(&buf[..len], b"\x01\x06denied");
+/// ```
+pub fn encode_postcard_into<E>(error: &E, buf: &mut [u8]) -> Result<usize, EncodeError>
+where
+  E: Error + ?Sized,
+{
+  let (len, truncated) = chain_len(error);
+  let mut writer = SliceWriter { buf, written: 0 };
+  let count = if truncated { len.saturating_add(1) } else { len };
+  writer.write_varint(count as u64)?;
+  for (_, message) in IndexedMessages::new(error) {
+    writer.write_message(message)?;
+  }
+  if truncated {
+    writer.write_message("...")?;
+  }
+  Ok(writer.written)
+}
+
+/// Error returned when encoding a chain fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EncodeError {
+  /// The output buffer is too small for the encoded chain.
+  BufferTooSmall,
+  /// A message failed to format, or had a different length when formatted
+  /// the second time.
+  Format,
+}
+
+impl fmt::Display for EncodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      EncodeError::BufferTooSmall => "buffer too small for the encoded chain",
+      EncodeError::Format => "failed to format an error message",
+    })
+  }
+}
+
+impl Error for EncodeError {}
+
+/// Writer filling a byte buffer, failing if the buffer is too small.
+pub(crate) struct SliceWriter<'b> {
+  pub(crate) buf: &'b mut [u8],
+  pub(crate) written: usize,
+}
+
+impl SliceWriter<'_> {
+  /// Write bytes at the current position.
+  pub(crate) fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+    let end = self
+      .written
+      .checked_add(bytes.len())
+      .ok_or(EncodeError::BufferTooSmall)?;
+    let target = self.buf.get_mut(self.written..end).ok_or(EncodeError::BufferTooSmall)?;
+    target.copy_from_slice(bytes);
+    self.written = end;
+    Ok(())
+  }
+
+  /// Write an unsigned LEB128 varint, as used by postcard for lengths.
+  pub(crate) fn write_varint(&mut self, mut value: u64) -> Result<(), EncodeError> {
+    loop {
+      let byte = (value & 0x7f) as u8;
+      value >>= 7;
+      if value == 0 {
+        return self.write_bytes(&[byte]);
+      }
+      self.write_bytes(&[byte | 0x80])?;
+    }
+  }
+
+  /// Write a message, prefixed with its byte length as a varint.
+  pub(crate) fn write_message<M>(&mut self, message: M) -> Result<(), EncodeError>
+  where
+    M: fmt::Display,
+  {
+    let mut counter = ByteCounter(0);
+    write!(counter, "{message}").map_err(|_| EncodeError::Format)?;
+    self.write_varint(counter.0 as u64)?;
+    let start = self.written;
+    let mut writer = LimitedWriter {
+      inner: &mut *self,
+      remaining: counter.0,
+      error: None,
+    };
+    let result = write!(writer, "{message}");
+    if let Some(error) = writer.error {
+      return Err(error);
+    }
+    result.map_err(|_| EncodeError::Format)?;
+    if self.written.saturating_sub(start) != counter.0 {
+      return Err(EncodeError::Format);
+    }
+    Ok(())
+  }
+}
+
+/// Writer counting the written bytes.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.0 = self.0.saturating_add(s.len());
+    Ok(())
+  }
+}
+
+/// Writer forwarding at most `remaining` bytes to a [`SliceWriter`].
+struct LimitedWriter<'w, 'b> {
+  inner: &'w mut SliceWriter<'b>,
+  remaining: usize,
+  error: Option<EncodeError>,
+}
+
+impl Write for LimitedWriter<'_, '_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let Some(remaining) = self.remaining.checked_sub(s.len()) else {
+      self.error = Some(EncodeError::Format);
+      return Err(fmt::Error);
+    };
+    self.remaining = remaining;
+    self.inner.write_bytes(s.as_bytes()).map_err(|e| {
+      self.error = Some(e);
+      fmt::Error
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{encode_postcard_into, EncodeError};
+  use crate::tests::message_chain;
+
+  #[test]
+  fn encode_chain() {
+    let input = message_chain(&["upload failed", "denied"]);
+    let mut buf = [0u8; 32];
+    let len = encode_postcard_into(&input, &mut buf);
+    assert_eq!(len, Ok(22));
+    assert_eq!(buf.get(..22), Some(&b"\x02\x0dupload failed\x06denied"[..]));
+  }
+
+  #[test]
+  fn encode_long_message_varint() {
+    let message = "a".repeat(200);
+    let input = message_chain(&[::alloc::boxed::Box::leak(message.into_boxed_str())]);
+    let mut buf = [0u8; 256];
+    let len = encode_postcard_into(&input, &mut buf);
+    assert_eq!(len, Ok(203));
+    assert_eq!(buf.get(..3), Some(&[0x01, 0xc8, 0x01][..]));
+  }
+
+  #[test]
+  fn buffer_too_small() {
+    let input = message_chain(&["upload failed", "denied"]);
+    let mut buf = [0u8; 8];
+    assert_eq!(encode_postcard_into(&input, &mut buf), Err(EncodeError::BufferTooSmall));
+  }
+}
@@ -0,0 +1,27 @@
+//! Prelude module, re-exporting the extension traits, the formatting wrappers,
+//! and the traits to customize the output.
+//!
+//! ```rust
+//! use ::display_full_error::prelude::*;
+//!
+//! let err = ::core::fmt::Error;
+//! assert_eq!(err.display_full().to_string(), "an error occurred when formatting an argument");
+//!
+//! let res: Result<(), ::core::fmt::Error> = Err(err);
+//! assert_eq!(res.display_full_or("ok").to_string(), "an error occurred when formatting an argument");
+//!
+//! let none: Option<&::core::fmt::Error> = None;
+//! assert_eq!(none.display_full_or("no error").to_string(), "no error");
+//!
+//! let category = ::display_full_error::DefaultClassifier.classify_chain(&err);
+//! assert_eq!(category, ::display_full_error::ErrorCategory::Other);
+//!
+//! let adapter = ::display_full_error::Sanitize.then(::display_full_error::RootFirst);
+//! assert_eq!(err.display_adapted(adapter).to_string(), "an error occurred when formatting an argument");
+//! ```
+
+pub use crate::{
+  ChainAdapter, ClassifyChain, DisplayFullError, DisplayFullErrorElided, DisplayFullErrorExt, DisplayFullErrorHead,
+  DisplayFullErrorShort, DisplayFullErrorTail, DisplayFullErrorWith, DisplayFullOptionExt, DisplayFullResultExt,
+  FormatOptions,
+};
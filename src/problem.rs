@@ -0,0 +1,79 @@
+//! RFC 7807 Problem Details output, for HTTP error responses.
+
+use crate::json::JsonEscape;
+use crate::{printed_sources, DisplayFullError, Error};
+use ::core::fmt::{self, Write};
+
+/// Media type of the Problem Details bodies, `application/problem+json`.
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Formatting wrapper to display an error as an
+/// [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) Problem Details JSON
+/// object.
+///
+/// The object has the following members:
+/// - `status`: the HTTP status code, if the second field is `Some`;
+/// - `title`: the message of the outermost error;
+/// - `detail`: the full chain, as [`DisplayFullError`](struct@DisplayFullError);
+/// - `causes`: an extension member with the messages of the sources, as an
+///   array of strings.
+///
+/// Use it as a response body with the [`PROBLEM_JSON_CONTENT_TYPE`] media
+/// type.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// assert_eq!(
+///   err.display_problem(Some(403)).to_string(),
+///   r#"{"status":403,"title":"permission denied","detail":"permission denied","causes":[]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorProblem<'e, E>(pub &'e E, pub Option<u16>)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorProblem<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('{')?;
+    if let Some(status) = self.1 {
+      write!(f, "\"status\":{status},")?;
+    }
+    f.write_str("\"title\":\"")?;
+    write!(JsonEscape(&mut *f), "{}", self.0)?;
+    f.write_str("\",\"detail\":\"")?;
+    write!(JsonEscape(&mut *f), "{}", DisplayFullError(self.0))?;
+    f.write_str("\",\"causes\":[")?;
+    for (i, source) in printed_sources(self.0).enumerate() {
+      if i > 0 {
+        f.write_char(',')?;
+      }
+      f.write_char('"')?;
+      write!(JsonEscape(&mut *f), "{source}")?;
+      f.write_char('"')?;
+    }
+    f.write_str("]}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn problem_with_causes() {
+    let input = message_chain(&["upload failed", "connection reset", "permission \"denied\""]);
+    let actual: String = input.display_problem(None).to_string();
+    let expected = String::from(
+      r#"{"title":"upload failed","detail":"upload failed: connection reset: permission \"denied\"","causes":["connection reset","permission \"denied\""]}"#,
+    );
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,280 @@
+//! Reporter limiting the number of reports of each chain per time window.
+
+use crate::fingerprint::fingerprint;
+use crate::{DisplayFullError, Error, ErrorReporter};
+use ::core::fmt;
+use ::std::collections::HashMap;
+use ::std::string::{String, ToString};
+use ::std::sync::{Mutex, PoisonError};
+use ::std::time::{Duration, Instant};
+use ::std::vec::Vec;
+
+/// Reporter forwarding each chain to an inner reporter at most `max_reports`
+/// times per time window.
+///
+/// Chains are identified by their
+/// [fingerprint](crate::DisplayFullErrorExt::fingerprint). Once the limit is
+/// reached, further reports of the chain are suppressed until its window
+/// closes. When the window closes, a [`SuppressedErrors`] note with the number
+/// of suppressed reports is forwarded: on the next report of the chain, or
+/// when calling [`flush`](Self::flush).
+///
+/// At most `capacity` chains are tracked. When a new chain is reported and the
+/// limit is reached, the closed windows are forgotten first, then the oldest
+/// one; the note of a forgotten window is forwarded immediately.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::std::time::Duration;
+/// use ::display_full_error::{ErrorReporter, RateLimitedReporter, StderrReporter};
+///
+/// let reporter = RateLimitedReporter::new(StderrReporter, 3, Duration::from_secs(60), 256);
+/// for _ in 0..1000 {
+///   // Printed 3 times
+///   reporter.report(&::std::io::Error::other("connection refused"));
+/// }
+/// // Call periodically, e.g. from a background task
+/// reporter.flush();
+/// ```
+#[derive(Debug)]
+pub struct RateLimitedReporter<R> {
+  inner: R,
+  max_reports: usize,
+  window: Duration,
+  capacity: usize,
+  windows: Mutex<HashMap<u64, Window>>,
+}
+
+/// Reports of a chain during the current time window.
+#[derive(Debug)]
+struct Window {
+  start: Instant,
+  reported: usize,
+  suppressed: usize,
+  /// Full chain, formatted on the first suppressed report.
+  message: Option<String>,
+}
+
+impl Window {
+  /// Get the note for the suppressed reports of this window, if any.
+  fn take_note(&mut self) -> Option<SuppressedErrors> {
+    let message = self.message.take()?;
+    let note = SuppressedErrors {
+      message,
+      count: self.suppressed,
+    };
+    self.suppressed = 0;
+    Some(note)
+  }
+}
+
+impl<R> RateLimitedReporter<R>
+where
+  R: ErrorReporter,
+{
+  /// Create a new reporter forwarding to `inner`, allowing each chain at most
+  /// `max_reports` times per `window`, and tracking up to `capacity` chains.
+  pub fn new(inner: R, max_reports: usize, window: Duration, capacity: usize) -> Self {
+    Self {
+      inner,
+      max_reports,
+      window,
+      capacity,
+      windows: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Get a reference to the inner reporter.
+  pub fn inner(&self) -> &R {
+    &self.inner
+  }
+
+  /// Forward the notes of the windows which are closed, and forget the
+  /// chains without recent reports.
+  pub fn flush(&self) {
+    self.flush_at(Instant::now());
+  }
+
+  /// Flush as if the current time was `now`.
+  fn flush_at(&self, now: Instant) {
+    let mut notes = Vec::new();
+    {
+      let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+      windows.retain(|_, window| {
+        if now.saturating_duration_since(window.start) < self.window {
+          return true;
+        }
+        notes.extend(window.take_note());
+        false
+      });
+    }
+    for note in notes {
+      self.inner.report(&note);
+    }
+  }
+
+  /// Report `error` as if the current time was `now`.
+  fn report_at(&self, error: &(dyn Error + 'static), now: Instant) {
+    if self.capacity == 0 {
+      self.inner.report(error);
+      return;
+    }
+    let fingerprint = fingerprint(error);
+    let mut notes = Vec::new();
+    let forward = {
+      let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+      if windows.len() >= self.capacity && !windows.contains_key(&fingerprint) {
+        windows.retain(|_, window| {
+          if now.saturating_duration_since(window.start) < self.window {
+            return true;
+          }
+          notes.extend(window.take_note());
+          false
+        });
+        if windows.len() >= self.capacity {
+          let oldest = windows
+            .iter()
+            .min_by_key(|(_, window)| window.start)
+            .map(|(&key, _)| key);
+          if let Some(mut window) = oldest.and_then(|key| windows.remove(&key)) {
+            notes.extend(window.take_note());
+          }
+        }
+      }
+      let window = windows.entry(fingerprint).or_insert(Window {
+        start: now,
+        reported: 0,
+        suppressed: 0,
+        message: None,
+      });
+      if now.saturating_duration_since(window.start) >= self.window {
+        notes.extend(window.take_note());
+        window.start = now;
+        window.reported = 0;
+      }
+      if window.reported < self.max_reports {
+        window.reported = window.reported.saturating_add(1);
+        true
+      } else {
+        if window.message.is_none() {
+          window.message = Some(DisplayFullError(error).to_string());
+        }
+        window.suppressed = window.suppressed.saturating_add(1);
+        false
+      }
+    };
+    for note in notes {
+      self.inner.report(&note);
+    }
+    if forward {
+      self.inner.report(error);
+    }
+  }
+}
+
+impl<R> ErrorReporter for RateLimitedReporter<R>
+where
+  R: ErrorReporter,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.report_at(error, Instant::now());
+  }
+}
+
+/// Note forwarded by [`RateLimitedReporter`] when a window with suppressed
+/// reports closes.
+///
+/// It is displayed as the full chain followed by `(+K suppressed)`.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SuppressedErrors {
+  /// Full chain of the suppressed error.
+  pub message: String,
+  /// Number of suppressed reports.
+  pub count: usize,
+}
+
+impl fmt::Display for SuppressedErrors {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} (+{} suppressed)", self.message, self.count)
+  }
+}
+
+impl Error for SuppressedErrors {}
+
+#[cfg(test)]
+mod tests {
+  use super::RateLimitedReporter;
+  use crate::tests::message_chain;
+  use crate::WriteReporter;
+  use ::std::time::{Duration, Instant};
+
+  #[test]
+  fn limit_and_note_on_next_window() {
+    let reporter = RateLimitedReporter::new(
+      WriteReporter::new(::std::vec::Vec::new()),
+      2,
+      Duration::from_secs(60),
+      16,
+    );
+    let start = Instant::now();
+    let error = message_chain(&["upload failed", "permission denied"]);
+    for _ in 0..5 {
+      reporter.report_at(&error, start);
+    }
+    reporter.report_at(&error, start + Duration::from_secs(60));
+    let RateLimitedReporter { inner, .. } = reporter;
+    let expected = "upload failed: permission denied\n\
+      upload failed: permission denied\n\
+      upload failed: permission denied (+3 suppressed)\n\
+      upload failed: permission denied\n";
+    assert_eq!(inner.into_inner(), expected.as_bytes());
+  }
+
+  #[test]
+  fn note_on_flush() {
+    let reporter = RateLimitedReporter::new(
+      WriteReporter::new(::std::vec::Vec::new()),
+      1,
+      Duration::from_secs(60),
+      16,
+    );
+    let start = Instant::now();
+    let error = message_chain(&["job failed"]);
+    reporter.report_at(&error, start);
+    reporter.report_at(&error, start);
+    reporter.flush_at(start + Duration::from_secs(30));
+    reporter.flush_at(start + Duration::from_secs(60));
+    reporter.flush_at(start + Duration::from_secs(120));
+    let RateLimitedReporter { inner, .. } = reporter;
+    assert_eq!(inner.into_inner(), b"job failed\njob failed (+1 suppressed)\n");
+  }
+
+  #[test]
+  fn bounded_windows() {
+    let reporter = RateLimitedReporter::new(
+      WriteReporter::new(::std::vec::Vec::new()),
+      1,
+      Duration::from_secs(60),
+      2,
+    );
+    let start = Instant::now();
+    let first = message_chain(&["first"]);
+    reporter.report_at(&first, start);
+    reporter.report_at(&first, start);
+    reporter.report_at(&message_chain(&["second"]), start + Duration::from_secs(1));
+    // Evicts the window of `first`, forwarding its note
+    reporter.report_at(&message_chain(&["third"]), start + Duration::from_secs(2));
+    assert_eq!(reporter.windows.lock().map(|w| w.len()).unwrap_or_default(), 2);
+    // The windows of `second` and `third` are closed and forgotten
+    reporter.report_at(&message_chain(&["fourth"]), start + Duration::from_secs(120));
+    assert_eq!(reporter.windows.lock().map(|w| w.len()).unwrap_or_default(), 1);
+    let RateLimitedReporter { inner, .. } = reporter;
+    assert_eq!(
+      inner.into_inner(),
+      b"first\nsecond\nfirst (+1 suppressed)\nthird\nfourth\n"
+    );
+  }
+}
@@ -0,0 +1,345 @@
+//! Report type, capturing an error with its backtrace.
+
+use crate::hook::{Indent, STYLE_END};
+use crate::wrap::{terminal_width, write_wrapped};
+use crate::{hook, sources, DisplayFullError, Error, ReportFormat, MESSAGE_LIMIT};
+use ::core::fmt::{self, Write as _};
+use ::std::backtrace::{Backtrace, BacktraceStatus};
+use ::std::format;
+use ::std::io::Write;
+use ::std::process::{ExitCode, Termination};
+use ::std::string::{String, ToString};
+
+/// Error report, capturing an error and a [`Backtrace`] at construction.
+///
+/// The default `Display` format prints the full chain on a single line, as
+/// [`DisplayFullError`](struct@DisplayFullError). The alternate format
+/// (`{:#}`) prints each source on its own line, followed by the backtrace if
+/// it was captured. The `Debug` format is the same as the alternate format, so
+/// the report can be returned from `main` as the error of a `Result`. The
+/// report also implements [`Termination`], printing the `Debug` format to
+/// stderr and exiting with a failure code. The `Debug` format can be customized
+/// with the global [`ReportHook`](crate::ReportHook), or at runtime with the
+/// `RUST_ERROR_FORMAT` environment variable (see [`ReportFormat`]).
+///
+/// The backtrace is captured with [`Backtrace::capture`], it is only enabled
+/// with the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables.
+///
+/// Long messages of the multiline format can be soft-wrapped with
+/// [`wrap_at`](Report::wrap_at) or [`wrap_to_terminal`](Report::wrap_to_terminal).
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::Report;
+///
+/// fn run() -> Result<(), Report<::std::io::Error>> {
+///   Err(::std::io::Error::other("permission denied"))?
+/// }
+///
+/// let report = run().unwrap_err();
+/// assert_eq!(report.to_string(), "permission denied");
+/// ```
+pub struct Report<E> {
+  error: E,
+  backtrace: Backtrace,
+  wrap: Option<usize>,
+}
+
+impl<E> Report<E>
+where
+  E: Error,
+{
+  /// Create a new report for `error`, capturing the current backtrace.
+  pub fn new(error: E) -> Self {
+    Self {
+      error,
+      backtrace: Backtrace::capture(),
+      wrap: None,
+    }
+  }
+
+  /// Soft-wrap the messages of the multiline format at `width` columns.
+  ///
+  /// Lines are broken at spaces, and continuation lines are indented to align
+  /// with the start of the message. Words longer than the available space are
+  /// not broken. When wrapping is enabled, the colors of the global
+  /// [`ReportHook`](crate::ReportHook) are not applied.
+  ///
+  /// ```rust
+  /// use ::display_full_error::Report;
+  ///
+  /// let report = Report::new(::std::io::Error::other("failed to read the configuration file")).wrap_at(20);
+  /// let actual = format!("{report:#}");
+  /// assert!(actual.starts_with("failed to read the\n  configuration file"));
+  /// ```
+  pub fn wrap_at(mut self, width: usize) -> Self {
+    self.wrap = Some(width);
+    self
+  }
+
+  /// Soft-wrap the messages of the multiline format at the width of the
+  /// terminal.
+  ///
+  /// The width is read from the `COLUMNS` environment variable when this
+  /// method is called, defaulting to 80 columns. See
+  /// [`wrap_at`](Report::wrap_at).
+  pub fn wrap_to_terminal(self) -> Self {
+    self.wrap_at(terminal_width())
+  }
+
+  /// Get a reference to the error of this report.
+  pub fn error(&self) -> &E {
+    &self.error
+  }
+
+  /// Get the backtrace captured when this report was created.
+  pub fn backtrace(&self) -> &Backtrace {
+    &self.backtrace
+  }
+
+  /// Unwrap this report, returning the error.
+  pub fn into_error(self) -> E {
+    self.error
+  }
+
+  /// Write the multiline report, indented as configured by the installed
+  /// [hook](crate::ReportHook::indent).
+  fn fmt_multiline(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.fmt_multiline_with(f, hook::indent())
+  }
+
+  /// Write the multiline report with the provided indentation.
+  fn fmt_multiline_with(&self, f: &mut fmt::Formatter<'_>, indent: Indent) -> fmt::Result {
+    match self.wrap {
+      Some(width) => fmt_pretty_wrapped(&self.error, width, indent, f)?,
+      None => {
+        fmt::Display::fmt(&self.error, f)?;
+        fmt_pretty_sources_styled(&self.error, f, indent, |_| None)?;
+      }
+    }
+    if self.backtrace.status() == BacktraceStatus::Captured {
+      write!(f, "\n\nStack backtrace:\n{}", self.backtrace)?;
+    }
+    Ok(())
+  }
+}
+
+/// Write the message of `error`, followed by its sources on separate lines.
+pub(crate) fn fmt_pretty<E>(error: &E, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  fmt::Display::fmt(error, f)?;
+  fmt_pretty_sources(error, f)
+}
+
+/// Write the sources of `error` on separate lines, after a `Caused by:` header.
+pub(crate) fn fmt_pretty_sources<E>(error: &E, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  fmt_pretty_sources_styled(error, f, Indent::default(), |_| None)
+}
+
+/// Write the sources of `error` like [`fmt_pretty_sources`], with a custom
+/// indentation, wrapping each message in the ANSI style returned by `style`
+/// for its depth, if any.
+pub(crate) fn fmt_pretty_sources_styled<E, S>(
+  error: &E,
+  f: &mut fmt::Formatter<'_>,
+  indent: Indent,
+  style: S,
+) -> fmt::Result
+where
+  E: Error + ?Sized,
+  S: Fn(usize) -> Option<&'static str>,
+{
+  let mut printed: u16 = 1;
+  for e in sources(error) {
+    if printed == 1 {
+      f.write_str("\n\nCaused by:")?;
+    }
+    f.write_char('\n')?;
+    indent.write_to(f, usize::from(printed))?;
+    if printed >= MESSAGE_LIMIT {
+      f.write_str("...")?;
+      break;
+    }
+    write!(f, "{}: ", printed.saturating_sub(1))?;
+    match style(usize::from(printed)) {
+      Some(style) => write!(f, "{style}{e}{STYLE_END}")?,
+      None => write!(f, "{e}")?,
+    }
+    printed = printed.saturating_add(1);
+  }
+  Ok(())
+}
+
+/// Write the multiline format of `error`, soft-wrapping messages at `width`
+/// columns.
+///
+/// Continuation lines of the outermost message are indented with one
+/// indentation unit, continuation lines of sources are aligned with the start
+/// of the message.
+fn fmt_pretty_wrapped<E>(error: &E, width: usize, indent: Indent, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+  E: Error + ?Sized,
+{
+  let mut head_indent = String::new();
+  indent.write_to(&mut head_indent, 1)?;
+  write_wrapped(f, &error.to_string(), 0, &head_indent, width)?;
+  let mut printed: u16 = 1;
+  for e in sources(error) {
+    if printed == 1 {
+      f.write_str("\n\nCaused by:")?;
+    }
+    let mut prefix = String::new();
+    indent.write_to(&mut prefix, usize::from(printed))?;
+    if printed >= MESSAGE_LIMIT {
+      write!(f, "\n{prefix}...")?;
+      break;
+    }
+    let label = format!("{}: ", printed.saturating_sub(1));
+    write!(f, "\n{prefix}{label}")?;
+    let start = prefix.chars().count().saturating_add(label.len());
+    let continuation = format!("{prefix}{:1$}", "", label.len());
+    write_wrapped(f, &e.to_string(), start, &continuation, width)?;
+    printed = printed.saturating_add(1);
+  }
+  Ok(())
+}
+
+impl<E> From<E> for Report<E>
+where
+  E: Error,
+{
+  fn from(error: E) -> Self {
+    Self::new(error)
+  }
+}
+
+impl<E> fmt::Display for Report<E>
+where
+  E: Error,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+      self.fmt_multiline(f)
+    } else {
+      fmt::Display::fmt(&DisplayFullError(&self.error), f)
+    }
+  }
+}
+
+impl<E> fmt::Debug for Report<E>
+where
+  E: Error,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let format = hook::resolve_format(ReportFormat::Pretty);
+    match (format, self.wrap) {
+      (ReportFormat::Pretty, Some(width)) => fmt_pretty_wrapped(&self.error, width, hook::indent(), f)?,
+      _ => fmt::Display::fmt(&hook::DisplayHooked(&self.error, format), f)?,
+    }
+    let multiline = matches!(format, ReportFormat::Pretty | ReportFormat::Tree);
+    if multiline && self.backtrace.status() == BacktraceStatus::Captured {
+      write!(f, "\n\nStack backtrace:\n{}", self.backtrace)?;
+    }
+    Ok(())
+  }
+}
+
+impl<E> Termination for Report<E>
+where
+  E: Error,
+{
+  fn report(self) -> ExitCode {
+    let _ = writeln!(::std::io::stderr().lock(), "Error: {self:?}");
+    ExitCode::FAILURE
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{fmt_pretty_sources_styled, Report};
+  use crate::hook::Indent;
+  use crate::tests::{message_chain, MessageError};
+  use ::alloc::format;
+  use ::alloc::string::{String, ToString};
+  use ::core::fmt;
+
+  #[test]
+  fn single_line_report() {
+    let report = Report::from(message_chain(&["upload failed", "permission denied"]));
+    assert_eq!(report.to_string(), String::from("upload failed: permission denied"));
+  }
+
+  #[test]
+  fn multiline_report() {
+    let report = Report::from(message_chain(&[
+      "upload failed",
+      "connection reset",
+      "permission denied",
+    ]));
+    let actual = format!("{report:#}");
+    let actual = actual.split("\n\nStack backtrace:").next().unwrap_or_default();
+    let expected = "upload failed\n\nCaused by:\n  0: connection reset\n  1: permission denied";
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn nested_indent() {
+    struct Nested<'e>(&'e MessageError);
+
+    impl fmt::Display for Nested<'_> {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_pretty_sources_styled(self.0, f, Indent::new("\t").nested(true), |_| None)
+      }
+    }
+
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let actual = Nested(&input).to_string();
+    let expected = "\n\nCaused by:\n\t0: connection reset\n\t\t1: permission denied";
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn wrapped_report() {
+    let report = Report::from(message_chain(&[
+      "failed to upload the file",
+      "the connection was reset by the peer",
+    ]))
+    .wrap_at(20);
+    let actual = format!("{report:#}");
+    let actual = actual.split("\n\nStack backtrace:").next().unwrap_or_default();
+    let expected = "failed to upload the\n  file\n\nCaused by:\n  0: the connection\n     was reset by\n     the peer";
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn multiline_report_indent() {
+    struct Multiline<'r>(&'r Report<MessageError>);
+
+    impl fmt::Display for Multiline<'_> {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_multiline_with(f, Indent::new("\t").nested(true))
+      }
+    }
+
+    let report = Report::from(message_chain(&[
+      "upload failed",
+      "connection reset",
+      "permission denied",
+    ]));
+    let actual = Multiline(&report).to_string();
+    let actual = actual.split("\n\nStack backtrace:").next().unwrap_or_default();
+    let expected = "upload failed\n\nCaused by:\n\t0: connection reset\n\t\t1: permission denied";
+    assert_eq!(actual, expected);
+    let report = report.wrap_at(20);
+    let actual = Multiline(&report).to_string();
+    let actual = actual.split("\n\nStack backtrace:").next().unwrap_or_default();
+    let expected = "upload failed\n\nCaused by:\n\t0: connection reset\n\t\t1: permission\n\t\t   denied";
+    assert_eq!(actual, expected);
+  }
+}
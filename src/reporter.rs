@@ -0,0 +1,284 @@
+//! Reporter backends, routing formatted chains to sinks.
+
+use crate::capture::capture_enabled;
+use crate::hook::{resolve_format, DisplayHooked};
+use crate::private::AsDynError;
+use crate::{record_last_error, Clock, DisplayFullError, DisplayRfc3339, Error, ReportFormat};
+use ::core::fmt;
+use ::std::boxed::Box;
+use ::std::fs::{File, OpenOptions};
+use ::std::io::{self, Write};
+use ::std::path::Path;
+use ::std::sync::{Mutex, OnceLock, PoisonError};
+use ::std::vec::Vec;
+
+/// Reporter installed with [`set_reporter`].
+static REPORTER: OnceLock<Box<dyn ErrorReporter>> = OnceLock::new();
+
+/// Sink receiving the errors passed to [`report`].
+///
+/// Built-in backends are [`StderrReporter`], [`WriteReporter`] (for files or
+/// any other output) and [`FnReporter`] (to forward to a logging library).
+/// Multiple reporters can be combined in a `Vec`.
+///
+/// Requires the `std` feature.
+pub trait ErrorReporter: Send + Sync {
+  /// Report an error, with its chain of sources.
+  fn report(&self, error: &(dyn Error + 'static));
+
+  /// Report multiple errors at once.
+  ///
+  /// The default implementation reports each error in order. Reporters
+  /// writing to an output override it to flush once per batch, see
+  /// [`BatchingReporter`](crate::BatchingReporter).
+  fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+    for error in errors {
+      self.report(*error);
+    }
+  }
+}
+
+impl<R> ErrorReporter for Box<R>
+where
+  R: ErrorReporter + ?Sized,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    (**self).report(error);
+  }
+
+  fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+    (**self).report_batch(errors);
+  }
+}
+
+impl<R> ErrorReporter for Vec<R>
+where
+  R: ErrorReporter,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    for reporter in self {
+      reporter.report(error);
+    }
+  }
+
+  fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+    for reporter in self {
+      reporter.report_batch(errors);
+    }
+  }
+}
+
+/// Reporter printing each chain to stderr, on its own line.
+///
+/// The output is the same as
+/// [`eprint_full`](crate::DisplayFullErrorExt::eprint_full). This is the
+/// reporter used if none is [installed](set_reporter).
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct StderrReporter;
+
+impl ErrorReporter for StderrReporter {
+  fn report(&self, error: &(dyn Error + 'static)) {
+    let format = resolve_format(ReportFormat::Full);
+    let _ = writeln!(io::stderr().lock(), "{}", DisplayHooked(error, format));
+  }
+}
+
+/// Reporter writing each chain to an [`io::Write`] output, on its own line.
+///
+/// Chains are formatted as [`DisplayFullError`](struct@DisplayFullError).
+/// Write failures are ignored. With [`timestamps`](Self::timestamps), each
+/// line starts with the time of the report.
+///
+/// Requires the `std` feature.
+#[derive(Debug)]
+pub struct WriteReporter<W> {
+  writer: Mutex<W>,
+  clock: Option<Clock>,
+}
+
+impl<W> WriteReporter<W>
+where
+  W: Write + Send,
+{
+  /// Create a new reporter writing to `writer`.
+  pub fn new(writer: W) -> Self {
+    Self {
+      writer: Mutex::new(writer),
+      clock: None,
+    }
+  }
+
+  /// Start each line with the time read from `clock`, as an RFC 3339 UTC date
+  /// followed by a space.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{system_clock, ErrorReporter, WriteReporter};
+  ///
+  /// let reporter = WriteReporter::new(::std::io::stderr()).timestamps(system_clock);
+  /// // e.g. `2024-02-29T12:34:56.789Z permission denied`
+  /// reporter.report(&::std::io::Error::other("permission denied"));
+  /// ```
+  #[must_use]
+  pub fn timestamps(mut self, clock: Clock) -> Self {
+    self.clock = Some(clock);
+    self
+  }
+
+  /// Unwrap this reporter, returning the output.
+  pub fn into_inner(self) -> W {
+    self.writer.into_inner().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl WriteReporter<File> {
+  /// Create a new reporter appending to the file at `path`, creating it if
+  /// needed.
+  pub fn open(path: &Path) -> io::Result<Self> {
+    OpenOptions::new().append(true).create(true).open(path).map(Self::new)
+  }
+}
+
+impl<W> ErrorReporter for WriteReporter<W>
+where
+  W: Write + Send,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.report_batch(&[error]);
+  }
+
+  fn report_batch(&self, errors: &[&(dyn Error + 'static)]) {
+    let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+    for error in errors {
+      if let Some(clock) = self.clock {
+        let _ = write!(writer, "{} ", DisplayRfc3339(clock()));
+      }
+      let _ = writeln!(writer, "{}", DisplayFullError(*error));
+    }
+    let _ = writer.flush();
+  }
+}
+
+/// Reporter calling a function with each error.
+///
+/// This is intended to forward the errors to a logging library.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{DisplayFullErrorExt, ErrorReporter, FnReporter};
+///
+/// let reporter = FnReporter(|e: &(dyn ::core::error::Error + 'static)| {
+///   // e.g. `log::error!("{}", e.display_full())`
+///   println!("{}", e.display_full());
+/// });
+/// reporter.report(&::std::io::Error::other("permission denied"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FnReporter<F>(pub F);
+
+impl<F> fmt::Debug for FnReporter<F> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("FnReporter").field(&"Fn").finish()
+  }
+}
+
+impl<F> ErrorReporter for FnReporter<F>
+where
+  F: Fn(&(dyn Error + 'static)) + Send + Sync,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    (self.0)(error);
+  }
+}
+
+/// Error returned by [`set_reporter`] when a reporter is already installed.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SetReporterError;
+
+impl fmt::Display for SetReporterError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("error reporter is already set")
+  }
+}
+
+impl Error for SetReporterError {}
+
+/// Install the global reporter used by [`report`].
+///
+/// The reporter can only be set once, further calls return an error.
+///
+/// Requires the `std` feature.
+pub fn set_reporter(reporter: Box<dyn ErrorReporter>) -> Result<(), SetReporterError> {
+  REPORTER.set(reporter).map_err(|_| SetReporterError)
+}
+
+/// Report an error with the installed reporter, or [`StderrReporter`] if none
+/// is installed.
+///
+/// If the [last error capture](crate::set_last_error_capture) is enabled, the
+/// error is also recorded for [`last_error`](crate::last_error).
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{report, set_reporter, WriteReporter};
+///
+/// set_reporter(Box::new(WriteReporter::new(::std::io::stdout()))).expect("the reporter is only set once");
+/// report(&::std::io::Error::other("permission denied"));
+/// ```
+pub fn report<E>(error: &E)
+where
+  E: AsDynError + ?Sized,
+{
+  let error = error.as_dyn_error();
+  if capture_enabled() {
+    record_last_error(error);
+  }
+  match REPORTER.get() {
+    Some(reporter) => reporter.report(error),
+    None => StderrReporter.report(error),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ErrorReporter, FnReporter, WriteReporter};
+  use crate::tests::message_chain;
+  use crate::Error;
+  use ::alloc::vec::Vec;
+  use ::core::sync::atomic::{AtomicUsize, Ordering};
+  use ::core::time::Duration;
+
+  #[test]
+  fn write_reporter_lines() {
+    let reporter = WriteReporter::new(Vec::new());
+    reporter.report(&message_chain(&["upload failed", "permission denied"]));
+    reporter.report(&message_chain(&["job failed"]));
+    assert_eq!(reporter.into_inner(), b"upload failed: permission denied\njob failed\n");
+  }
+
+  #[test]
+  fn write_reporter_timestamps() {
+    let reporter = WriteReporter::new(Vec::new()).timestamps(|| Duration::from_millis(1_709_210_096_789));
+    reporter.report(&message_chain(&["upload failed", "permission denied"]));
+    assert_eq!(
+      reporter.into_inner(),
+      b"2024-02-29T12:34:56.789Z upload failed: permission denied\n"
+    );
+  }
+
+  #[test]
+  fn combined_reporters() {
+    let count = AtomicUsize::new(0);
+    let counter = |_: &(dyn Error + 'static)| {
+      count.fetch_add(1, Ordering::Relaxed);
+    };
+    let reporters = ::alloc::vec![FnReporter(&counter), FnReporter(&counter)];
+    reporters.report(&message_chain(&["upload failed"]));
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+  }
+}
@@ -0,0 +1,196 @@
+//! Display wrapper for results, printing the full chain of errors.
+
+#[cfg(feature = "alloc")]
+use crate::ChainedError;
+use crate::{private, DisplayFullError, Error};
+use ::core::fmt;
+
+/// Formatting wrapper to display a [`Result`], printing the full error chain
+/// for `Err` values.
+///
+/// `Err` values are displayed as with [`DisplayFullError`]. `Ok` values are
+/// displayed either as a fixed placeholder, or with the `Display`
+/// implementation of `T`. This is convenient for status endpoints and debug
+/// dumps of stored results.
+#[derive(Debug)]
+pub struct DisplayFullResult<'r, T, E>
+where
+  E: Error,
+{
+  result: &'r Result<T, E>,
+  ok: OkFormat<'r, T>,
+}
+
+// Manual implementations, to avoid requiring `T: Clone` and `E: Clone`
+impl<T, E> Clone for DisplayFullResult<'_, T, E>
+where
+  E: Error,
+{
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T, E> Copy for DisplayFullResult<'_, T, E> where E: Error {}
+
+/// How to display `Ok` values.
+#[derive(Debug)]
+enum OkFormat<'r, T> {
+  Placeholder(&'r str),
+  Display(fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result),
+}
+
+impl<T> Clone for OkFormat<'_, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for OkFormat<'_, T> {}
+
+impl<'r, T, E> DisplayFullResult<'r, T, E>
+where
+  E: Error,
+{
+  /// Display `Ok` values with their `Display` implementation.
+  pub fn new(result: &'r Result<T, E>) -> Self
+  where
+    T: fmt::Display,
+  {
+    Self {
+      result,
+      ok: OkFormat::Display(<T as fmt::Display>::fmt),
+    }
+  }
+
+  /// Display `Ok` values as the provided placeholder.
+  pub fn with_placeholder(result: &'r Result<T, E>, placeholder: &'r str) -> Self {
+    Self {
+      result,
+      ok: OkFormat::Placeholder(placeholder),
+    }
+  }
+}
+
+impl<T, E> fmt::Display for DisplayFullResult<'_, T, E>
+where
+  E: Error,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (self.result, self.ok) {
+      (Ok(_), OkFormat::Placeholder(placeholder)) => f.write_str(placeholder),
+      (Ok(value), OkFormat::Display(display)) => display(value, f),
+      (Err(e), _) => fmt::Display::fmt(&DisplayFullError(e), f),
+    }
+  }
+}
+
+/// Extension trait providing convenience methods on [results](Result).
+///
+/// This trait provides a blanket implementation for all results where the
+/// error implements [the standard `Error` trait](::core::error::Error).
+pub trait DisplayFullResultExt<T, E>: private::SealedResult
+where
+  E: Error,
+{
+  /// Get a reference to this result wrapped in a [`DisplayFullResult`]
+  /// formatter, displaying `Ok` values with their `Display` implementation.
+  fn display_full_result(&self) -> DisplayFullResult<'_, T, E>
+  where
+    T: fmt::Display;
+
+  /// Get a reference to this result wrapped in a [`DisplayFullResult`]
+  /// formatter, displaying `Ok` values as the provided placeholder.
+  fn display_full_or<'r>(&'r self, placeholder: &'r str) -> DisplayFullResult<'r, T, E>;
+
+  /// Wrap the error with a context message, in a [`ChainedError`].
+  ///
+  /// This provides `anyhow`-like context without leaving the format of this
+  /// crate: the context is the outer message of the chain.
+  ///
+  /// Requires the `alloc` feature.
+  ///
+  /// ```rust
+  /// use ::display_full_error::{DisplayFullErrorExt, DisplayFullResultExt};
+  ///
+  /// let result: Result<(), _> = Err(::std::io::Error::other("permission denied"));
+  /// let err = result.context_full("failed to read config").unwrap_err();
+  /// assert_eq!(err.to_string_full(), "failed to read config: permission denied");
+  /// ```
+  #[cfg(feature = "alloc")]
+  fn context_full<C>(self, context: C) -> Result<T, ChainedError>
+  where
+    C: fmt::Display,
+    E: Send + Sync + 'static;
+}
+
+impl<T, E> private::SealedResult for Result<T, E> where E: Error {}
+
+impl<T, E> DisplayFullResultExt<T, E> for Result<T, E>
+where
+  E: Error,
+{
+  fn display_full_result(&self) -> DisplayFullResult<'_, T, E>
+  where
+    T: fmt::Display,
+  {
+    DisplayFullResult::new(self)
+  }
+
+  fn display_full_or<'r>(&'r self, placeholder: &'r str) -> DisplayFullResult<'r, T, E> {
+    DisplayFullResult::with_placeholder(self, placeholder)
+  }
+
+  #[cfg(feature = "alloc")]
+  fn context_full<C>(self, context: C) -> Result<T, ChainedError>
+  where
+    C: fmt::Display,
+    E: Send + Sync + 'static,
+  {
+    self.map_err(|e| ChainedError::new(context, e))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{PermissionError, UploadError};
+  #[cfg(feature = "alloc")]
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+  use ::alloc::vec::Vec;
+
+  #[test]
+  fn display_ok_value() {
+    let input: Result<u32, UploadError> = Ok(42);
+    let actual: String = input.display_full_result().to_string();
+    assert_eq!(actual, String::from("42"));
+  }
+
+  #[test]
+  fn display_ok_placeholder() {
+    let input: Result<Vec<u8>, UploadError> = Ok(Vec::new());
+    let actual: String = input.display_full_or("ok").to_string();
+    assert_eq!(actual, String::from("ok"));
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn context_full() {
+    let input: Result<u32, UploadError> = Err(UploadError::Permission(PermissionError));
+    let actual = input.context_full("job failed").map_err(|e| e.to_string_full());
+    assert_eq!(
+      actual,
+      Err(String::from("job failed: upload failed: permission denied"))
+    );
+    let input: Result<u32, UploadError> = Ok(42);
+    assert_eq!(input.context_full("job failed").ok(), Some(42));
+  }
+
+  #[test]
+  fn display_err_chain() {
+    let input: Result<Vec<u8>, UploadError> = Err(UploadError::Permission(PermissionError));
+    let actual: String = input.display_full_or("ok").to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+  }
+}
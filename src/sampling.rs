@@ -0,0 +1,163 @@
+//! Reporter forwarding only a sample of the reports of each chain.
+
+use crate::fingerprint::fingerprint;
+use crate::{DisplayFullError, Error, ErrorReporter};
+use ::core::fmt;
+use ::std::collections::VecDeque;
+use ::std::string::{String, ToString};
+use ::std::sync::{Mutex, PoisonError};
+
+/// Reporter forwarding one report out of every `rate` reports of each chain.
+///
+/// Chains are identified by their
+/// [fingerprint](crate::DisplayFullErrorExt::fingerprint). The first report of
+/// a chain is always forwarded, then every `rate`-th report. Forwarded reports
+/// are wrapped in a [`SampledError`] note, so the output shows the sample
+/// rate. A `rate` of `0` or `1` forwards every report unchanged.
+///
+/// This is intended for extremely hot failure paths, where even
+/// [rate limiting](crate::RateLimitedReporter) is too chatty. A counter is
+/// kept for up to `capacity` distinct chains, the oldest ones are forgotten
+/// first, so a forgotten chain is forwarded again on its next report. A
+/// `capacity` of `0` forwards every report unchanged.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::{ErrorReporter, SamplingReporter, StderrReporter};
+///
+/// let reporter = SamplingReporter::new(StderrReporter, 100, 256);
+/// for _ in 0..1000 {
+///   // Printed 10 times, as `connection refused (sampled 1/100)`
+///   reporter.report(&::std::io::Error::other("connection refused"));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SamplingReporter<R> {
+  inner: R,
+  rate: u64,
+  capacity: usize,
+  /// Fingerprints with their number of reports, oldest first.
+  counts: Mutex<VecDeque<(u64, u64)>>,
+}
+
+impl<R> SamplingReporter<R>
+where
+  R: ErrorReporter,
+{
+  /// Create a new reporter forwarding to `inner` one report out of every
+  /// `rate` reports of each chain, and tracking up to `capacity` chains.
+  pub fn new(inner: R, rate: u64, capacity: usize) -> Self {
+    Self {
+      inner,
+      rate,
+      capacity,
+      counts: Mutex::new(VecDeque::new()),
+    }
+  }
+
+  /// Get a reference to the inner reporter.
+  pub fn inner(&self) -> &R {
+    &self.inner
+  }
+
+  /// Count a report of the chain with `fingerprint`, and check if it is
+  /// sampled.
+  fn should_report(&self, fingerprint: u64) -> bool {
+    let mut counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, count)) = counts.iter_mut().find(|(seen, _)| *seen == fingerprint) {
+      let sampled = count.checked_rem(self.rate) == Some(0);
+      *count = count.wrapping_add(1);
+      return sampled;
+    }
+    if counts.len() >= self.capacity {
+      counts.pop_front();
+    }
+    counts.push_back((fingerprint, 1));
+    true
+  }
+}
+
+impl<R> ErrorReporter for SamplingReporter<R>
+where
+  R: ErrorReporter,
+{
+  fn report(&self, error: &(dyn Error + 'static)) {
+    if self.rate <= 1 || self.capacity == 0 {
+      self.inner.report(error);
+      return;
+    }
+    if self.should_report(fingerprint(error)) {
+      self.inner.report(&SampledError {
+        message: DisplayFullError(error).to_string(),
+        rate: self.rate,
+      });
+    }
+  }
+}
+
+/// Note forwarded by [`SamplingReporter`] for each sampled report.
+///
+/// It is displayed as the full chain followed by `(sampled 1/N)`.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SampledError {
+  /// Full chain of the sampled error.
+  pub message: String,
+  /// Sample rate, one report is forwarded out of `rate`.
+  pub rate: u64,
+}
+
+impl fmt::Display for SampledError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} (sampled 1/{})", self.message, self.rate)
+  }
+}
+
+impl Error for SampledError {}
+
+#[cfg(test)]
+mod tests {
+  use super::SamplingReporter;
+  use crate::tests::message_chain;
+  use crate::{ErrorReporter, WriteReporter};
+
+  #[test]
+  fn sample_each_chain() {
+    let reporter = SamplingReporter::new(WriteReporter::new(::std::vec::Vec::new()), 3, 16);
+    let upload = message_chain(&["upload failed", "permission denied"]);
+    let job = message_chain(&["job failed"]);
+    for _ in 0..7 {
+      reporter.report(&upload);
+    }
+    reporter.report(&job);
+    let SamplingReporter { inner, .. } = reporter;
+    let expected = "upload failed: permission denied (sampled 1/3)\n\
+      upload failed: permission denied (sampled 1/3)\n\
+      upload failed: permission denied (sampled 1/3)\n\
+      job failed (sampled 1/3)\n";
+    assert_eq!(inner.into_inner(), expected.as_bytes());
+  }
+
+  #[test]
+  fn rate_one_forwards_unchanged() {
+    let reporter = SamplingReporter::new(WriteReporter::new(::std::vec::Vec::new()), 1, 16);
+    reporter.report(&message_chain(&["job failed"]));
+    reporter.report(&message_chain(&["job failed"]));
+    let SamplingReporter { inner, .. } = reporter;
+    assert_eq!(inner.into_inner(), b"job failed\njob failed\n");
+  }
+
+  #[test]
+  fn evict_oldest_when_full() {
+    let reporter = SamplingReporter::new(WriteReporter::new(::std::vec::Vec::new()), 3, 1);
+    reporter.report(&message_chain(&["first"]));
+    reporter.report(&message_chain(&["first"]));
+    reporter.report(&message_chain(&["second"]));
+    reporter.report(&message_chain(&["first"]));
+    let SamplingReporter { inner, .. } = reporter;
+    let expected = "first (sampled 1/3)\nsecond (sampled 1/3)\nfirst (sampled 1/3)\n";
+    assert_eq!(inner.into_inner(), expected.as_bytes());
+  }
+}
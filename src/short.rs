@@ -0,0 +1,69 @@
+//! Short display mode, printing only the outermost message and the root
+//! cause.
+
+use crate::{chain_len, DisplayFullErrorExt, Error};
+use ::core::fmt;
+
+/// Formatting wrapper to display only the outermost message and the root
+/// cause of an error chain.
+///
+/// Messages are formatted on a single line, separated with `: `. If the chain
+/// has intermediate messages, they are replaced with `...`, e.g.
+/// `upload failed: ...: permission denied`. The root cause is the
+/// [`deepest_source`](DisplayFullErrorExt::deepest_source): if the chain is
+/// longer than [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, this is the
+/// last source before the limit.
+///
+/// This is intended for UI notifications and alert titles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorShort<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorShort<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(&self.0, f)?;
+    if chain_len(self.0).0 > 2 {
+      f.write_str(": ...")?;
+    }
+    if let Some(root) = self.0.deepest_source() {
+      f.write_str(": ")?;
+      fmt::Display::fmt(root, f)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, CyclicError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn short_long_chain() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let actual: String = input.display_short().to_string();
+    let expected = String::from("upload failed: ...: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn short_short_chain() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual: String = input.display_short().to_string();
+    assert_eq!(actual, String::from("upload failed: permission denied"));
+    let input = message_chain(&["upload failed"]);
+    let actual: String = input.display_short().to_string();
+    assert_eq!(actual, String::from("upload failed"));
+  }
+
+  #[test]
+  fn short_over_limit() {
+    let actual: String = CyclicError.display_short().to_string();
+    assert_eq!(actual, String::from("cycle detected: ...: cycle detected"));
+  }
+}
@@ -0,0 +1,159 @@
+//! Statistics of the reported chains, grouped by root cause.
+
+use crate::fingerprint::fingerprint;
+use crate::{sources, Error, ErrorReporter, MESSAGE_LIMIT};
+use ::core::fmt;
+use ::std::collections::HashMap;
+use ::std::string::{String, ToString};
+use ::std::sync::{Mutex, PoisonError};
+use ::std::vec::Vec;
+
+/// Collector counting the recorded chains by root cause.
+///
+/// The root cause is the deepest source of the chain (or the error itself if
+/// it has no source), identified by its
+/// [fingerprint](crate::DisplayFullErrorExt::fingerprint). The collector is
+/// also an [`ErrorReporter`], so it can be combined with other reporters.
+///
+/// Requires the `std` feature.
+///
+/// ```rust
+/// use ::display_full_error::ChainStats;
+///
+/// let stats = ChainStats::new();
+/// stats.record(&::std::io::Error::other("permission denied"));
+/// stats.record(&::std::io::Error::other("permission denied"));
+/// stats.record(&::std::io::Error::other("disk full"));
+/// assert_eq!(stats.summary().to_string(), "3 errors by root cause:\n  2 permission denied\n  1 disk full");
+/// ```
+#[derive(Debug, Default)]
+pub struct ChainStats {
+  causes: Mutex<HashMap<u64, RootCauseCount>>,
+}
+
+/// Number of chains recorded for a root cause.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RootCauseCount {
+  /// Message of the root cause.
+  pub message: String,
+  /// Number of chains with this root cause.
+  pub count: u64,
+}
+
+impl ChainStats {
+  /// Create a new empty collector.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Record a chain.
+  pub fn record<E>(&self, error: &E)
+  where
+    E: Error + ?Sized,
+  {
+    let root = sources(error).take(usize::from(MESSAGE_LIMIT.saturating_sub(1))).last();
+    let key = match root {
+      Some(root) => fingerprint(root),
+      None => fingerprint(error),
+    };
+    let mut causes = self.causes.lock().unwrap_or_else(PoisonError::into_inner);
+    let entry = causes.entry(key).or_insert_with(|| RootCauseCount {
+      message: match root {
+        Some(root) => root.to_string(),
+        None => error.to_string(),
+      },
+      count: 0,
+    });
+    entry.count = entry.count.saturating_add(1);
+  }
+
+  /// Forget all the recorded chains.
+  pub fn clear(&self) {
+    self.causes.lock().unwrap_or_else(PoisonError::into_inner).clear();
+  }
+
+  /// Get a snapshot of the statistics.
+  ///
+  /// Root causes are sorted by decreasing count, then by message.
+  pub fn summary(&self) -> ChainStatsSummary {
+    let mut causes: Vec<RootCauseCount> = self
+      .causes
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .values()
+      .cloned()
+      .collect();
+    causes.sort_by(|left, right| {
+      right
+        .count
+        .cmp(&left.count)
+        .then_with(|| left.message.cmp(&right.message))
+    });
+    ChainStatsSummary { causes }
+  }
+}
+
+impl ErrorReporter for ChainStats {
+  fn report(&self, error: &(dyn Error + 'static)) {
+    self.record(error);
+  }
+}
+
+/// Snapshot of [`ChainStats`].
+///
+/// It is displayed as the total number of chains, followed by a line per root
+/// cause with its count.
+///
+/// Requires the `std` feature.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChainStatsSummary {
+  causes: Vec<RootCauseCount>,
+}
+
+impl ChainStatsSummary {
+  /// Get the root causes, sorted by decreasing count, then by message.
+  pub fn causes(&self) -> &[RootCauseCount] {
+    &self.causes
+  }
+
+  /// Get the total number of recorded chains.
+  pub fn total(&self) -> u64 {
+    self
+      .causes
+      .iter()
+      .fold(0, |total, cause| total.saturating_add(cause.count))
+  }
+}
+
+impl fmt::Display for ChainStatsSummary {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} errors by root cause:", self.total())?;
+    for cause in &self.causes {
+      write!(f, "\n  {} {}", cause.count, cause.message)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ChainStats;
+  use crate::tests::message_chain;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn group_by_root_cause() {
+    let stats = ChainStats::new();
+    stats.record(&message_chain(&["upload failed", "permission denied"]));
+    stats.record(&message_chain(&["download failed", "permission denied"]));
+    stats.record(&message_chain(&["upload failed", "disk full"]));
+    let summary = stats.summary();
+    assert_eq!(summary.total(), 3);
+    let expected = String::from("3 errors by root cause:\n  2 permission denied\n  1 disk full");
+    assert_eq!(summary.to_string(), expected);
+    stats.clear();
+    assert_eq!(stats.summary().total(), 0);
+  }
+}
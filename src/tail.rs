@@ -0,0 +1,65 @@
+//! Tail display mode, printing only the deepest messages of the chain.
+
+use crate::{chain_len, sources, Error};
+use ::core::fmt;
+
+/// Formatting wrapper to display only the deepest messages of an error chain.
+///
+/// The second field is the maximum number of messages to print. Messages are
+/// formatted on a single line, separated with `: `. If outer messages are
+/// omitted, the output starts with `...: `. The chain is considered up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the output
+/// ends with `: ...` as with [`DisplayFullError`](struct@crate::DisplayFullError).
+///
+/// This is useful for very long chains, where the root causes are usually the
+/// most relevant part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorTail<'e, E>(pub &'e E, pub usize)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorTail<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (len, truncated) = chain_len(self.0);
+    let skip = len.saturating_sub(self.1);
+    if skip == 0 {
+      fmt::Display::fmt(&self.0, f)?;
+    } else {
+      f.write_str("...")?;
+    }
+    for e in sources(self.0).take(len.saturating_sub(1)).skip(skip.saturating_sub(1)) {
+      f.write_str(": ")?;
+      fmt::Display::fmt(e, f)?;
+    }
+    if truncated {
+      f.write_str(": ...")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn tail_of_long_chain() {
+    let input = message_chain(&["job failed", "upload failed", "connection reset", "permission denied"]);
+    let actual: String = input.display_tail(2).to_string();
+    let expected = String::from("...: connection reset: permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn tail_of_short_chain() {
+    let input = message_chain(&["upload failed", "permission denied"]);
+    let actual: String = input.display_tail(2).to_string();
+    let expected = String::from("upload failed: permission denied");
+    assert_eq!(actual, expected);
+  }
+}
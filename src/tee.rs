@@ -0,0 +1,81 @@
+//! Tee output, formatting the chain once for a human and a machine sink.
+
+use crate::json::JsonEscape;
+use crate::{sources, Error, MESSAGE_LIMIT};
+use ::core::fmt::{self, Write};
+
+/// Write the chain of `error` to two outputs in a single pass: the single-line
+/// form to `human` and the JSON form to `json`.
+///
+/// The `human` output is the same as
+/// [`DisplayFullError`](struct@crate::DisplayFullError), and the `json` output
+/// is the same as [`DisplayFullErrorJson`](struct@crate::DisplayFullErrorJson).
+/// Each message is formatted once and written to both outputs, which halves
+/// the formatting cost for services logging both forms.
+///
+/// ```rust
+/// use ::display_full_error::write_tee;
+///
+/// let err = ::std::io::Error::other("invalid \"name\"");
+/// let mut human = String::new();
+/// let mut json = String::new();
+/// write_tee(&err, &mut human, &mut json).expect("writing to a string never fails");
+/// assert_eq!(human, r#"invalid "name""#);
+/// assert_eq!(json, r#"["invalid \"name\""]"#);
+/// ```
+pub fn write_tee<E, H, J>(error: &E, human: &mut H, json: &mut J) -> fmt::Result
+where
+  E: Error + ?Sized,
+  H: Write + ?Sized,
+  J: Write + ?Sized,
+{
+  json.write_str("[\"")?;
+  write!(Tee(&mut *human, JsonEscape(&mut *json)), "{error}")?;
+  json.write_char('"')?;
+  let mut printed: u16 = 1;
+  for e in sources(error) {
+    if printed >= MESSAGE_LIMIT {
+      human.write_str(": ...")?;
+      json.write_str(",\"...\"")?;
+      break;
+    }
+    human.write_str(": ")?;
+    json.write_str(",\"")?;
+    write!(Tee(&mut *human, JsonEscape(&mut *json)), "{e}")?;
+    json.write_char('"')?;
+    printed = printed.saturating_add(1);
+  }
+  json.write_char(']')
+}
+
+/// Writer forwarding the written text to two writers.
+struct Tee<A, B>(A, B);
+
+impl<A, B> Write for Tee<A, B>
+where
+  A: Write,
+  B: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.0.write_str(s)?;
+    self.1.write_str(s)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::write_tee;
+  use crate::tests::message_chain;
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn tee_matches_single_outputs() {
+    let input = message_chain(&["upload failed", "path \"C:\\tmp\"", "permission denied"]);
+    let mut human = String::new();
+    let mut json = String::new();
+    assert_eq!(write_tee(&input, &mut human, &mut json), Ok(()));
+    assert_eq!(human, input.display_full().to_string());
+    assert_eq!(json, input.display_json().to_string());
+  }
+}
@@ -0,0 +1,107 @@
+//! RFC 3339 timestamps, with a pluggable clock.
+
+use ::core::fmt;
+use ::core::time::Duration;
+
+/// Clock returning the current time, as the duration since the Unix epoch.
+///
+/// With the `std` feature, [`system_clock`] reads the system time. Without it,
+/// provide a function reading the time source of your platform, such as an
+/// RTC.
+pub type Clock = fn() -> Duration;
+
+/// Read the system time, as the duration since the Unix epoch.
+///
+/// Times before the Unix epoch are returned as the epoch.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn system_clock() -> Duration {
+  ::std::time::SystemTime::now()
+    .duration_since(::std::time::UNIX_EPOCH)
+    .unwrap_or_default()
+}
+
+/// Formatting wrapper to display a time as an RFC 3339 UTC date with
+/// milliseconds, such as `2024-01-31T12:00:00.000Z`.
+///
+/// The time is the duration since the Unix epoch, as returned by a [`Clock`].
+///
+/// ```rust
+/// use ::core::time::Duration;
+/// use ::display_full_error::DisplayRfc3339;
+///
+/// let time = Duration::from_millis(1_709_210_096_789);
+/// assert_eq!(DisplayRfc3339(time).to_string(), "2024-02-29T12:34:56.789Z");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DisplayRfc3339(pub Duration);
+
+impl fmt::Display for DisplayRfc3339 {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let secs = self.0.as_secs();
+    let days = secs / 86_400;
+    let secs_of_day = secs % 86_400;
+    // Civil date from the number of days, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days.saturating_add(719_468);
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = doe
+      .saturating_sub(doe / 1460)
+      .saturating_add(doe / 36_524)
+      .saturating_sub(doe / 146_096)
+      / 365;
+    let doy = doe.saturating_sub(
+      yoe
+        .saturating_mul(365)
+        .saturating_add(yoe / 4)
+        .saturating_sub(yoe / 100),
+    );
+    let mp = doy.saturating_mul(5).saturating_add(2) / 153;
+    let day = doy
+      .saturating_sub(mp.saturating_mul(153).saturating_add(2) / 5)
+      .saturating_add(1);
+    let month = if mp < 10 {
+      mp.saturating_add(3)
+    } else {
+      mp.saturating_sub(9)
+    };
+    let year = era
+      .saturating_mul(400)
+      .saturating_add(yoe)
+      .saturating_add(u64::from(month <= 2));
+    write!(
+      f,
+      "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+      secs_of_day / 3600,
+      secs_of_day / 60 % 60,
+      secs_of_day % 60,
+      self.0.subsec_millis()
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DisplayRfc3339;
+  use ::alloc::string::{String, ToString};
+  use ::core::time::Duration;
+
+  #[test]
+  fn rfc3339_dates() {
+    assert_eq!(
+      DisplayRfc3339(Duration::ZERO).to_string(),
+      String::from("1970-01-01T00:00:00.000Z")
+    );
+    let time = Duration::from_millis(951_782_400_001);
+    assert_eq!(
+      DisplayRfc3339(time).to_string(),
+      String::from("2000-02-29T00:00:00.001Z")
+    );
+    let time = Duration::from_secs(253_402_300_799);
+    assert_eq!(
+      DisplayRfc3339(time).to_string(),
+      String::from("9999-12-31T23:59:59.000Z")
+    );
+  }
+}
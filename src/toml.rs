@@ -0,0 +1,73 @@
+//! TOML output mode, printing the chain as a TOML fragment.
+
+use crate::json::JsonEscape;
+use crate::{printed_sources, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the error chain as a TOML fragment.
+///
+/// The fragment has two keys: `error`, the message of the outermost error,
+/// and `causes`, the messages of the sources as an array of strings. Messages
+/// are written as TOML basic strings. The chain is printed up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the
+/// `causes` array ends with a `"..."` string.
+///
+/// This is intended for tools persisting the last error in TOML state or
+/// configuration files.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("invalid \"name\"");
+/// assert_eq!(err.display_toml().to_string(), "error = \"invalid \\\"name\\\"\"\ncauses = []");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorToml<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorToml<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("error = \"")?;
+    write!(JsonEscape(&mut *f), "{}", self.0)?;
+    f.write_str("\"\ncauses = [")?;
+    for (i, source) in printed_sources(self.0).enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      f.write_char('"')?;
+      write!(JsonEscape(&mut *f), "{source}")?;
+      f.write_char('"')?;
+    }
+    f.write_char(']')
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn toml_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_toml().to_string();
+    let expected = String::from("error = \"upload failed\"\ncauses = [\"permission denied\"]");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn toml_escapes_messages() {
+    let input = message_chain(&["upload failed", "path C:\\tmp", "line 1\nline 2\u{7f}"]);
+    let actual: String = input.display_toml().to_string();
+    let expected = String::from(
+      r#"error = "upload failed"
+causes = ["path C:\\tmp", "line 1\nline 2\u007f"]"#,
+    );
+    assert_eq!(actual, expected);
+  }
+}
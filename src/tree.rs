@@ -0,0 +1,103 @@
+//! Tree output mode, drawing the chain with box-drawing connectors.
+
+use crate::{sources, Error, MESSAGE_LIMIT};
+use ::core::fmt;
+
+/// Connector drawn before each source.
+const BRANCH: &str = "└─ ";
+
+/// Indentation of each level below the connector of the parent.
+const LEVEL_INDENT: &str = "   ";
+
+/// Maximum nesting level: deeper sources are drawn as a flat list at this
+/// level, so the output stays linear in the length of the chain.
+const MAX_LEVEL: u16 = 8;
+
+/// Formatting wrapper to display the error chain as a tree.
+///
+/// The message of the outermost error is on the first line, followed by each
+/// source on its own line, nested below its parent with a `└─` connector. This
+/// is the familiar look of modern CLI diagnostics. Past 8 levels, the sources
+/// are no longer indented further, to keep long chains readable. The chain is
+/// printed up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the last
+/// line is `...`.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("permission denied");
+/// assert_eq!(err.display_tree().to_string(), "permission denied");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorTree<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorTree<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self.0, f)?;
+    fmt_tree_sources(self.0, f, |f, _, e| fmt::Display::fmt(e, f))
+  }
+}
+
+/// Write the sources of `error` as the branches of a tree, writing each
+/// message with `write_message`, which receives the depth of the message.
+pub(crate) fn fmt_tree_sources<E, W>(error: &E, f: &mut fmt::Formatter<'_>, mut write_message: W) -> fmt::Result
+where
+  E: Error + ?Sized,
+  W: FnMut(&mut fmt::Formatter<'_>, usize, &(dyn Error + 'static)) -> fmt::Result,
+{
+  let mut printed: u16 = 1;
+  for e in sources(error) {
+    f.write_str("\n")?;
+    for _ in 1..printed.min(MAX_LEVEL) {
+      f.write_str(LEVEL_INDENT)?;
+    }
+    f.write_str(BRANCH)?;
+    if printed >= MESSAGE_LIMIT {
+      return f.write_str("...");
+    }
+    write_message(f, usize::from(printed), e)?;
+    printed = printed.saturating_add(1);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{LEVEL_INDENT, MAX_LEVEL};
+  use crate::tests::{message_chain, CyclicError, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn tree_nested_levels() {
+    let input = message_chain(&["upload failed", "connection reset", "permission denied"]);
+    let actual: String = input.display_tree().to_string();
+    let expected = String::from("upload failed\n└─ connection reset\n   └─ permission denied");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn tree_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_tree().to_string();
+    assert_eq!(actual, String::from("upload failed\n└─ permission denied"));
+  }
+
+  #[test]
+  fn tree_indent_is_capped() {
+    let actual: String = CyclicError.display_tree().to_string();
+    let max_indent = LEVEL_INDENT.repeat(usize::from(MAX_LEVEL - 1));
+    let lines: ::alloc::vec::Vec<&str> = actual.lines().collect();
+    assert_eq!(lines.get(1).copied(), Some("└─ cycle detected"));
+    assert_eq!(lines.last().map(|line| line.starts_with(&max_indent)), Some(true));
+    assert!(lines
+      .iter()
+      .all(|line| line.len() <= max_indent.len() + "└─ cycle detected".len()));
+  }
+}
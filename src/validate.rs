@@ -0,0 +1,90 @@
+//! Validation of the single-line invariant of error messages.
+
+use crate::messages::IndexedMessages;
+use crate::Error;
+use ::core::fmt::{self, Write};
+
+/// Check that every message of the chain fits on a single line.
+///
+/// Returns an error for the first message containing a newline or another
+/// control character.
+pub(crate) fn validate_single_line<E>(error: &E) -> Result<(), SingleLineError>
+where
+  E: Error + ?Sized,
+{
+  for (index, message) in IndexedMessages::new(error) {
+    let mut finder = ControlCharFinder(None);
+    // Formatting stops early once a control char is found
+    let _ = write!(finder, "{message}");
+    if let Some(character) = finder.0 {
+      return Err(SingleLineError { index, character });
+    }
+  }
+  Ok(())
+}
+
+/// Error returned when a message of the chain is not a single line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SingleLineError {
+  /// Index of the offending message in the chain, `0` is the outermost error
+  pub index: usize,
+  /// First newline or control character found in the message
+  pub character: char,
+}
+
+impl fmt::Display for SingleLineError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "error message at index {} contains the control character {:?}",
+      self.index, self.character
+    )
+  }
+}
+
+impl Error for SingleLineError {}
+
+/// Writer recording the first control char, failing once it is found.
+struct ControlCharFinder(Option<char>);
+
+impl Write for ControlCharFinder {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    match s.chars().find(|c| c.is_control()) {
+      Some(c) => {
+        self.0 = Some(c);
+        Err(fmt::Error)
+      }
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn single_line_chain_is_valid() {
+    let input = UploadError::Permission(PermissionError);
+    assert_eq!(input.validate_single_line(), Ok(()));
+  }
+
+  #[test]
+  fn multiline_source_is_invalid() {
+    let input = message_chain(&["upload failed", "permission denied", "line 1\nline 2"]);
+    let actual = input.validate_single_line();
+    assert_eq!(
+      actual,
+      Err(SingleLineError {
+        index: 2,
+        character: '\n'
+      })
+    );
+    let actual: String = actual.unwrap_err().to_string();
+    let expected = String::from("error message at index 2 contains the control character '\\n'");
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,75 @@
+//! Soft-wrapping of long messages in the multiline format.
+
+use ::core::fmt::{self, Write};
+
+/// Width used when the terminal width is unknown.
+const DEFAULT_WIDTH: usize = 80;
+
+/// Get the width of the terminal, from the `COLUMNS` environment variable.
+///
+/// Falls back to 80 columns if the variable is missing or invalid.
+pub(crate) fn terminal_width() -> usize {
+  ::std::env::var("COLUMNS")
+    .ok()
+    .and_then(|columns| columns.trim().parse::<usize>().ok())
+    .filter(|&columns| columns > 0)
+    .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Write `message`, breaking lines at spaces so they don't exceed `width`
+/// columns.
+///
+/// `start` is the column of the first character, continuation lines start
+/// with `indent`. Words longer than the available space are not broken.
+/// Columns are counted in `char`s.
+pub(crate) fn write_wrapped<W>(f: &mut W, message: &str, start: usize, indent: &str, width: usize) -> fmt::Result
+where
+  W: Write + ?Sized,
+{
+  let indent_len = indent.chars().count();
+  let mut column = start;
+  for (i, line) in message.split('\n').enumerate() {
+    if i > 0 {
+      write!(f, "\n{indent}")?;
+      column = indent_len;
+    }
+    let mut line_start = true;
+    for word in line.split(' ') {
+      let len = word.chars().count();
+      if !line_start {
+        if column.saturating_add(1).saturating_add(len) > width {
+          write!(f, "\n{indent}")?;
+          column = indent_len;
+        } else {
+          f.write_char(' ')?;
+          column = column.saturating_add(1);
+        }
+      }
+      f.write_str(word)?;
+      column = column.saturating_add(len);
+      line_start = false;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::write_wrapped;
+  use ::alloc::string::String;
+
+  #[test]
+  fn wrap_with_indent() {
+    let mut actual = String::from("  0: ");
+    let result = write_wrapped(&mut actual, "failed to read the configuration file", 5, "     ", 20);
+    assert_eq!(result, Ok(()));
+    assert_eq!(actual, "  0: failed to read\n     the\n     configuration\n     file");
+  }
+
+  #[test]
+  fn keep_short_message() {
+    let mut actual = String::new();
+    assert_eq!(write_wrapped(&mut actual, "permission denied", 0, "  ", 80), Ok(()));
+    assert_eq!(actual, "permission denied");
+  }
+}
@@ -0,0 +1,101 @@
+//! XML output mode, for legacy logging systems.
+
+use crate::{printed_sources, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the error chain as an XML element.
+///
+/// The output is an `<error>` element containing a `<message>` element with
+/// the message of the outermost error, followed by a `<cause>` element per
+/// source. Messages are escaped as XML character data; the chars which are
+/// not allowed in XML 1.0 documents (most ASCII control chars) are replaced
+/// with `U+FFFD`. The chain is printed up to
+/// [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is longer the last
+/// cause is `...`.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("invalid <name>");
+/// assert_eq!(err.display_xml().to_string(), "<error><message>invalid &lt;name&gt;</message></error>");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorXml<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorXml<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("<error><message>")?;
+    write!(XmlEscape(&mut *f), "{}", self.0)?;
+    f.write_str("</message>")?;
+    for source in printed_sources(self.0) {
+      f.write_str("<cause>")?;
+      write!(XmlEscape(&mut *f), "{source}")?;
+      f.write_str("</cause>")?;
+    }
+    f.write_str("</error>")
+  }
+}
+
+/// Writer escaping the text written to the inner writer as XML character data.
+struct XmlEscape<W>(W);
+
+impl<W> Write for XmlEscape<W>
+where
+  W: Write,
+{
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let mut rest = s;
+    while let Some(index) = rest.find(needs_escape) {
+      let (text, special) = rest.split_at(index);
+      self.0.write_str(text)?;
+      let mut chars = special.chars();
+      match chars.next() {
+        Some('&') => self.0.write_str("&amp;")?,
+        Some('<') => self.0.write_str("&lt;")?,
+        Some('>') => self.0.write_str("&gt;")?,
+        Some('"') => self.0.write_str("&quot;")?,
+        Some('\'') => self.0.write_str("&apos;")?,
+        Some(_) => self.0.write_char(char::REPLACEMENT_CHARACTER)?,
+        None => {}
+      }
+      rest = chars.as_str();
+    }
+    self.0.write_str(rest)
+  }
+}
+
+/// Check if `c` must be escaped or replaced in XML character data.
+fn needs_escape(c: char) -> bool {
+  matches!(c, '&' | '<' | '>' | '"' | '\'' | '\u{FFFE}' | '\u{FFFF}')
+    || (c.is_ascii_control() && !matches!(c, '\t' | '\n' | '\r' | '\u{7F}'))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn xml_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_xml().to_string();
+    let expected = String::from("<error><message>upload failed</message><cause>permission denied</cause></error>");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn xml_escapes_messages() {
+    let input = message_chain(&["a & b", "\"quoted\" 'text'\u{0}"]);
+    let actual: String = input.display_xml().to_string();
+    let expected = String::from(
+      "<error><message>a &amp; b</message><cause>&quot;quoted&quot; &apos;text&apos;\u{FFFD}</cause></error>",
+    );
+    assert_eq!(actual, expected);
+  }
+}
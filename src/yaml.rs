@@ -0,0 +1,70 @@
+//! YAML output mode, printing the chain as a block sequence.
+
+use crate::json::JsonEscape;
+use crate::{printed_sources, Error};
+use ::core::fmt::{self, Write};
+
+/// Formatting wrapper to display the error chain as a YAML block sequence.
+///
+/// Each message of the chain is an item of the sequence, on its own line,
+/// starting with the outermost error. Messages are written as double-quoted
+/// scalars, so they are never interpreted as other YAML values. The chain is
+/// printed up to [`MESSAGE_LIMIT`](crate::MESSAGE_LIMIT) messages, if it is
+/// longer the sequence ends with a `"..."` item.
+///
+/// This is intended for tools embedding error reports in YAML documents, such
+/// as Kubernetes status fields.
+///
+/// ```rust
+/// use ::display_full_error::DisplayFullErrorExt;
+///
+/// let err = ::std::io::Error::other("invalid: \"name\"");
+/// assert_eq!(err.display_yaml().to_string(), r#"- "invalid: \"name\"""#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayFullErrorYaml<'e, E>(pub &'e E)
+where
+  E: Error + ?Sized;
+
+impl<E> fmt::Display for DisplayFullErrorYaml<'_, E>
+where
+  E: Error + ?Sized,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("- \"")?;
+    write!(JsonEscape(&mut *f), "{}", self.0)?;
+    f.write_char('"')?;
+    for source in printed_sources(self.0) {
+      f.write_str("\n- \"")?;
+      write!(JsonEscape(&mut *f), "{source}")?;
+      f.write_char('"')?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::{message_chain, PermissionError, UploadError};
+  use crate::DisplayFullErrorExt;
+  use ::alloc::string::{String, ToString};
+
+  #[test]
+  fn yaml_simple_chain() {
+    let input = UploadError::Permission(PermissionError);
+    let actual: String = input.display_yaml().to_string();
+    let expected = String::from("- \"upload failed\"\n- \"permission denied\"");
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn yaml_escapes_messages() {
+    let input = message_chain(&["key: value # comment", "line 1\nline 2\u{85}"]);
+    let actual: String = input.display_yaml().to_string();
+    let expected = String::from(
+      r#"- "key: value # comment"
+- "line 1\nline 2\u0085""#,
+    );
+    assert_eq!(actual, expected);
+  }
+}